@@ -0,0 +1,197 @@
+//! Generates a `#[test]` for every `input/dayN*.txt` that has a matching `.expected` sidecar.
+//! The input directory comes from `aoc.toml` if there is one, see `src/config.rs`.
+//! Tests of sealed inputs are ignored when there is no key to open them, and so is the registry's
+//! check of the variants on the full inputs, see the `sealed_without_key` cfg. A key copied into
+//! the key file later is only noticed after a rebuild, e.g. `touch build.rs`.
+//!
+//! The sidecar format is described in `src/expected.rs`, which is shared with the library.
+//!
+//! Also hashes every `src/dayN.rs`, together with the library modules shared by the days, into
+//! `SOURCE_HASHES` for the runner's answer cache.
+
+extern crate alloc;
+
+#[allow(dead_code)]
+#[path = "src/config.rs"]
+mod config;
+#[allow(dead_code)]
+#[path = "src/expected.rs"]
+mod expected;
+#[path = "src/hash.rs"]
+mod hash;
+#[allow(dead_code)]
+#[path = "src/seal.rs"]
+mod seal;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// split `day12.example1` into `("day12", "example1")` and `day12` into `("day12", "full")`
+fn split_stem(stem: &str) -> (&str, String) {
+    match stem.split_once('.') {
+        Some((day, name)) => (day, name.replace(|c: char| !c.is_alphanumeric(), "_")),
+        None => (stem, String::from("full")),
+    }
+}
+
+fn main() {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let config = config::Config::discover(Path::new(&manifest_dir), Path::new(&manifest_dir))
+        .unwrap_or_else(|err| panic!("{}", err));
+    if let Some(path) = &config.path {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    let input_dir = config.input_dir();
+    println!("cargo:rerun-if-changed={}", input_dir.display());
+
+    println!("cargo:rerun-if-env-changed={}", seal::KEY_VARIABLE);
+    println!("cargo::rustc-check-cfg=cfg(sealed_without_key)");
+    // a missing file would rerun the build script every time
+    let key_path = config.key_path();
+    if key_path.exists() {
+        println!("cargo:rerun-if-changed={}", key_path.display());
+    }
+    let has_key = std::env::var_os(seal::KEY_VARIABLE).is_some() || key_path.exists();
+    let mut sealed_without_key = false;
+
+    let mut days: BTreeMap<String, String> = BTreeMap::new();
+
+    let mut entries = std::fs::read_dir(&input_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for input_path in entries {
+        let file_name = input_path.file_name().unwrap().to_str().unwrap();
+        let stem = match file_name.strip_suffix(".txt") {
+            Some(stem) if stem.starts_with("day") => stem,
+            _ => continue,
+        };
+
+        let expected_path = input_dir.join(format!("{}.expected", stem));
+        let expected = match std::fs::read_to_string(&expected_path) {
+            Ok(content) => expected::parse(&content)
+                .unwrap_or_else(|err| panic!("{}: {}", expected_path.display(), err)),
+            Err(_) => continue,
+        };
+
+        let (day, name) = split_stem(stem);
+        let tests = days.entry(day.to_string()).or_default();
+        let sealed = std::fs::read_to_string(&input_path)
+            .is_ok_and(|input| seal::is_sealed(&input))
+            && !has_key;
+        sealed_without_key |= sealed;
+
+        for (part, answer) in &expected.answers {
+            writeln!(tests, "    #[test]").unwrap();
+            if let Some(reason) = &expected.ignore {
+                writeln!(tests, "    #[ignore = {:?}]", reason).unwrap();
+            } else if sealed {
+                writeln!(
+                    tests,
+                    "    #[ignore = \"sealed input and no key to open it\"]"
+                )
+                .unwrap();
+            }
+            writeln!(tests, "    fn part{}_{}() {{", part, name).unwrap();
+            writeln!(tests, "        let input = include_str!({:?});", input_path).unwrap();
+            writeln!(
+                tests,
+                "        let input = crate::seal::open_in_crate(input);"
+            )
+            .unwrap();
+            writeln!(
+                tests,
+                "        let parsed = crate::{}::parse(&crate::input::normalize(&input));",
+                day
+            )
+            .unwrap();
+            writeln!(
+                tests,
+                "        assert_eq!(crate::{}::part{}(&parsed).to_string().trim_end(), {:?});",
+                day,
+                part,
+                answer.trim_end()
+            )
+            .unwrap();
+            writeln!(tests, "    }}\n").unwrap();
+        }
+    }
+
+    if sealed_without_key {
+        println!("cargo:rustc-cfg=sealed_without_key");
+    }
+
+    let mut generated = String::new();
+    for (day, tests) in days {
+        writeln!(generated, "mod {} {{\n{}\n}}\n", day, tests.trim_end()).unwrap();
+    }
+
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    std::fs::write(Path::new(&out_dir).join("examples.rs"), generated).unwrap();
+    std::fs::write(
+        Path::new(&out_dir).join("source_hashes.rs"),
+        source_hashes(&config.src_dir()),
+    )
+    .unwrap();
+}
+
+/// the day of `dayN.rs`
+fn source_day(path: &Path) -> Option<u8> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("day")?
+        .strip_suffix(".rs")?
+        .parse()
+        .ok()
+}
+
+/// every `.rs` file below `dir` except the runner's in `bin/`, sorted
+fn library_sources(dir: &Path, sources: &mut Vec<std::path::PathBuf>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "bin") {
+                library_sources(&path, sources);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            sources.push(path);
+        }
+    }
+    sources.sort();
+}
+
+/// `SOURCE_HASHES`, by day the hash of its `dayN.rs` and of the shared modules it may use, so a
+/// change to e.g. `grid.rs` invalidates the cached answers of every day
+fn source_hashes(src_dir: &Path) -> String {
+    let mut sources = vec![];
+    library_sources(src_dir, &mut sources);
+
+    let mut shared = vec![];
+    let mut days = BTreeMap::new();
+    for path in sources {
+        println!("cargo:rerun-if-changed={}", path.display());
+        let source = std::fs::read(&path).unwrap();
+        match source_day(&path) {
+            Some(day) => {
+                days.insert(day, source);
+            }
+            None => {
+                let name = path.strip_prefix(src_dir).unwrap().to_string_lossy();
+                shared.extend_from_slice(name.as_bytes());
+                shared.push(0);
+                shared.extend_from_slice(&source);
+                shared.push(0);
+            }
+        }
+    }
+
+    let mut generated = String::from("pub const SOURCE_HASHES: &[(u8, u64)] = &[\n");
+    for (day, source) in days {
+        let hash = hash::fnv1a(&[shared.as_slice(), &source].concat());
+        writeln!(generated, "    ({}, {:#018x}),", day, hash).unwrap();
+    }
+    generated + "];\n"
+}
@@ -0,0 +1,86 @@
+//! A plugin with a sliding window solution of day 1, as a template for solutions kept outside
+//! this crate
+//!
+//! ```text
+//! cargo build --example day1_plugin
+//! mkdir -p plugins && cp target/debug/examples/libday1_plugin.so plugins/
+//! cargo run --bin run -- crosscheck 1
+//! ```
+
+use aoc2021::plugin::{PluginInfo, PluginSolver, ABI_VERSION, OK, PANICKED};
+use std::ffi::{c_char, CString};
+
+/// the number of depths that are larger than the one `window` before them
+fn increases(input: &str, window: usize) -> Result<usize, String> {
+    let depths = input
+        .lines()
+        .map(|line| line.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    // comparing sums of windows that share all but their ends only compares the ends
+    Ok(depths
+        .iter()
+        .zip(depths.iter().skip(window))
+        .filter(|(first, last)| last > first)
+        .count())
+}
+
+unsafe fn solve(
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+    window: usize,
+) -> i32 {
+    let input = std::str::from_utf8(std::slice::from_raw_parts(input, input_len));
+    let (status, text) = match input
+        .map_err(|err| err.to_string())
+        .and_then(|input| increases(input, window))
+    {
+        Ok(count) => (OK, count.to_string()),
+        Err(err) => (PANICKED, err),
+    };
+    *answer = CString::new(text).unwrap_or_default().into_raw();
+    status
+}
+
+unsafe extern "C" fn part1(input: *const u8, input_len: usize, answer: *mut *mut c_char) -> i32 {
+    solve(input, input_len, answer, 1)
+}
+
+unsafe extern "C" fn part2(input: *const u8, input_len: usize, answer: *mut *mut c_char) -> i32 {
+    solve(input, input_len, answer, 3)
+}
+
+unsafe extern "C" fn free(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
+
+static SOLVERS: [PluginSolver; 2] = [
+    PluginSolver {
+        day: 1,
+        part: 1,
+        variant: c"window".as_ptr(),
+        solve: part1,
+    },
+    PluginSolver {
+        day: 1,
+        part: 2,
+        variant: c"window".as_ptr(),
+        solve: part2,
+    },
+];
+
+static INFO: PluginInfo = PluginInfo {
+    abi_version: ABI_VERSION,
+    name: c"example".as_ptr(),
+    solvers: SOLVERS.as_ptr(),
+    solver_count: SOLVERS.len(),
+    free,
+};
+
+#[no_mangle]
+pub extern "C" fn aoc2021_plugin() -> *const PluginInfo {
+    &INFO
+}
@@ -0,0 +1,58 @@
+/* Generated by aoc2021_ffi::header, do not edit.
+ * Regenerate with `AOC2021_UPDATE_HEADER=1 cargo test header_in_sync` */
+
+#ifndef AOC2021_H
+#define AOC2021_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* status codes returned by aoc2021_solve */
+#define AOC2021_OK               0 /* answer holds the answer */
+#define AOC2021_NULL_ARGUMENT    1 /* answer or input is NULL */
+#define AOC2021_INVALID_UTF8     2 /* the input is not valid UTF-8 */
+#define AOC2021_UNKNOWN_SOLVER   3 /* there is no solver for the day and part */
+#define AOC2021_PANICKED         4 /* the solver panicked, e.g. on malformed input */
+
+/* Solves one part of a day for the input_len bytes at input.
+ * Unless the status is AOC2021_NULL_ARGUMENT, *answer is set to a NUL-terminated string that
+ * has to be released with aoc2021_free: the answer for AOC2021_OK, an error message otherwise. */
+int32_t aoc2021_solve(uint8_t day, uint8_t part, const uint8_t *input, size_t input_len, char **answer);
+
+/* Releases a string returned by aoc2021_solve, NULL is ignored. */
+void aoc2021_free(char *answer);
+
+/* Plugins are shared libraries whose solvers the runner loads from the plugin directory.
+ * A plugin exports aoc2021_plugin, returning a static description of its solvers. */
+#define AOC2021_PLUGIN_ABI_VERSION 1
+
+/* One part of a day, solve reports like aoc2021_solve and sets *answer to a string that the
+ * runner releases with the plugin's free. variant is unique among the plugin's solvers of the
+ * same day and part. */
+typedef struct {
+    uint8_t day;
+    uint8_t part;
+    const char *variant;
+    int32_t (*solve)(const uint8_t *, size_t, char **);
+} Aoc2021PluginSolver;
+
+/* abi_version is AOC2021_PLUGIN_ABI_VERSION, name prefixes the variant names, name/variant. */
+typedef struct {
+    uint32_t abi_version;
+    const char *name;
+    const Aoc2021PluginSolver *solvers;
+    size_t solver_count;
+    void (*free)(char *);
+} Aoc2021PluginInfo;
+
+const Aoc2021PluginInfo *aoc2021_plugin(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AOC2021_H */
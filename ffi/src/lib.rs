@@ -0,0 +1,333 @@
+//! C ABI for driving the solvers from other languages, see `include/aoc2021.h`
+//!
+//! Built as a cdylib of its own: a cdylib needs `std`'s panic runtime, so the C ABI can't be a
+//! part of the `no_std` library. The header is generated by [`header`], `header_in_sync` fails
+//! when it is out of date and rewrites it when run with `AOC2021_UPDATE_HEADER=1`.
+//!
+//! The status codes, prototypes and plugin structs in the header are rendered from their Rust
+//! definitions through [`CType`], only the comments are written by hand. The order of struct
+//! fields is not checked, keep the `fields!` lists in declaration order.
+
+use aoc2021::budget::Budget;
+use aoc2021::plugin::{
+    self, EntryPoint, PluginInfo, PluginSolver, INVALID_UTF8, NULL_ARGUMENT, OK, PANICKED,
+    UNKNOWN_SOLVER,
+};
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// name, code and description of every status code, the header's `AOC2021_*` defines
+const STATUS_CODES: &[(&str, i32, &str)] = &[
+    ("OK", OK, "answer holds the answer"),
+    ("NULL_ARGUMENT", NULL_ARGUMENT, "answer or input is NULL"),
+    ("INVALID_UTF8", INVALID_UTF8, "the input is not valid UTF-8"),
+    (
+        "UNKNOWN_SOLVER",
+        UNKNOWN_SOLVER,
+        "there is no solver for the day and part",
+    ),
+    (
+        "PANICKED",
+        PANICKED,
+        "the solver panicked, e.g. on malformed input",
+    ),
+];
+
+/// Solves one part of a day for the `input_len` bytes at `input`
+///
+/// Unless the status is `NULL_ARGUMENT`, `*answer` is set to a NUL-terminated string that has to
+/// be released with [`aoc2021_free`], the answer for `OK` and an error message otherwise.
+///
+/// # Safety
+///
+/// `input` must point to `input_len` readable bytes and `answer` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_solve(
+    day: u8,
+    part: u8,
+    input: *const u8,
+    input_len: usize,
+    answer: *mut *mut c_char,
+) -> i32 {
+    if answer.is_null() || input.is_null() {
+        return NULL_ARGUMENT;
+    }
+    let (status, message) = solve(day, part, input, input_len);
+    // answers and messages never contain NUL, but don't hand out a truncated answer if they do
+    let message = CString::new(message).unwrap_or_default();
+    *answer = message.into_raw();
+    status
+}
+
+unsafe fn solve(day: u8, part: u8, input: *const u8, input_len: usize) -> (i32, String) {
+    let input = match std::str::from_utf8(std::slice::from_raw_parts(input, input_len)) {
+        Ok(input) => input,
+        Err(err) => return (INVALID_UTF8, err.to_string()),
+    };
+    let solver = match registry::solver(day, part, DEFAULT_VARIANT) {
+        Some(solver) => solver,
+        None => {
+            return (
+                UNKNOWN_SOLVER,
+                format!("no solver for Day {} Part {}", day, part),
+            )
+        }
+    };
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let parsed = registry::parse(day, input).expect("registered solver without a day");
+        (solver.solve)(&*parsed, &Budget::unlimited())
+    }));
+    match result {
+        Ok(Ok(answer)) => (OK, answer),
+        // unreachable with an unlimited budget
+        Ok(Err(exhausted)) => (PANICKED, exhausted.to_string()),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            (PANICKED, format!("solver panicked: {}", message))
+        }
+    }
+}
+
+/// Releases a string returned by [`aoc2021_solve`], NULL is ignored
+///
+/// # Safety
+///
+/// `answer` must come from [`aoc2021_solve`] and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn aoc2021_free(answer: *mut c_char) {
+    if !answer.is_null() {
+        drop(CString::from_raw(answer));
+    }
+}
+
+/// A type of the C ABI and how C declares it
+trait CType {
+    /// a declaration of `name` with this type, an abstract declarator if `name` is empty
+    fn declare(name: &str) -> String;
+}
+
+macro_rules! c_types {
+    ($($rust:ty => $c:literal,)*) => {$(
+        impl CType for $rust {
+            fn declare(name: &str) -> String {
+                match name {
+                    "" => String::from($c.trim_end()),
+                    _ => format!("{}{}", $c, name),
+                }
+            }
+        }
+    )*};
+}
+
+c_types! {
+    () => "void ",
+    u8 => "uint8_t ",
+    u32 => "uint32_t ",
+    i32 => "int32_t ",
+    usize => "size_t ",
+    *const u8 => "const uint8_t *",
+    *const c_char => "const char *",
+    *mut c_char => "char *",
+    *mut *mut c_char => "char **",
+    *const PluginSolver => "const Aoc2021PluginSolver *",
+    *const PluginInfo => "const Aoc2021PluginInfo *",
+}
+
+/// the parameter list of a C function, `names` are empty for a function pointer
+fn parameters(types: &[fn(&str) -> String], names: &[&str]) -> String {
+    match types {
+        [] => String::from("void"),
+        _ => types
+            .iter()
+            .zip(names)
+            .map(|(declare, name)| declare(name))
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// A function type of the C ABI, declared as a function of that name
+trait Signature {
+    fn declare_function(name: &str, names: &[&str]) -> String;
+}
+
+macro_rules! signatures {
+    ($(($($parameter:ident),*))*) => {$(
+        impl<R: CType, $($parameter: CType),*> CType for unsafe extern "C" fn($($parameter),*) -> R {
+            fn declare(name: &str) -> String {
+                let types: &[fn(&str) -> String] = &[$($parameter::declare),*];
+                let names = vec![""; types.len()];
+                R::declare(&format!("(*{})({})", name, parameters(types, &names)))
+            }
+        }
+
+        impl<R: CType, $($parameter: CType),*> Signature for unsafe extern "C" fn($($parameter),*) -> R {
+            fn declare_function(name: &str, names: &[&str]) -> String {
+                let types: &[fn(&str) -> String] = &[$($parameter::declare),*];
+                assert_eq!(types.len(), names.len(), "parameter names of {}", name);
+                R::declare(&format!("{}({})", name, parameters(types, names)))
+            }
+        }
+    )*};
+}
+
+signatures! { () (A) (A, B, C) (A, B, C, D, E) }
+
+/// the prototype of `function`, e.g. `aoc2021_free as unsafe extern "C" fn(_) -> _`
+fn prototype<F: Signature>(name: &str, _function: F, names: &[&str]) -> String {
+    format!("{};\n", F::declare_function(name, names))
+}
+
+/// the declaration of a struct field with the type that `field` returns
+fn field<S, T: CType>(name: &str, _field: fn(&S) -> &T) -> String {
+    format!("    {};\n", T::declare(name))
+}
+
+/// The fields of a `#[repr(C)]` struct, naming all of them or failing to compile
+macro_rules! fields {
+    ($struct:ident { $($field:ident),* $(,)? }) => {{
+        let _all_fields = |$struct { $($field: _),* }: &$struct| ();
+        [$(field(stringify!($field), |value: &$struct| &value.$field)),*].concat()
+    }};
+}
+
+/// The C header declaring the functions and status codes of this crate and the plugin interface
+/// of [`aoc2021::plugin`]
+pub fn header() -> String {
+    let mut header = String::from(
+        "\
+/* Generated by aoc2021_ffi::header, do not edit.
+ * Regenerate with `AOC2021_UPDATE_HEADER=1 cargo test header_in_sync` */
+
+#ifndef AOC2021_H
+#define AOC2021_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern \"C\" {
+#endif
+
+/* status codes returned by aoc2021_solve */
+",
+    );
+    for (name, code, description) in STATUS_CODES {
+        header += &format!(
+            "#define AOC2021_{:<16} {} /* {} */\n",
+            name, code, description
+        );
+    }
+    header += "
+/* Solves one part of a day for the input_len bytes at input.
+ * Unless the status is AOC2021_NULL_ARGUMENT, *answer is set to a NUL-terminated string that
+ * has to be released with aoc2021_free: the answer for AOC2021_OK, an error message otherwise. */
+";
+    header += &prototype(
+        "aoc2021_solve",
+        aoc2021_solve as unsafe extern "C" fn(_, _, _, _, _) -> _,
+        &["day", "part", "input", "input_len", "answer"],
+    );
+    header += "
+/* Releases a string returned by aoc2021_solve, NULL is ignored. */
+";
+    header += &prototype(
+        "aoc2021_free",
+        aoc2021_free as unsafe extern "C" fn(_) -> _,
+        &["answer"],
+    );
+    header += "
+/* Plugins are shared libraries whose solvers the runner loads from the plugin directory.
+ * A plugin exports aoc2021_plugin, returning a static description of its solvers. */
+";
+    header += &format!(
+        "#define AOC2021_PLUGIN_ABI_VERSION {}\n",
+        plugin::ABI_VERSION
+    );
+    header += "
+/* One part of a day, solve reports like aoc2021_solve and sets *answer to a string that the
+ * runner releases with the plugin's free. variant is unique among the plugin's solvers of the
+ * same day and part. */
+typedef struct {
+";
+    header += &fields!(PluginSolver {
+        day,
+        part,
+        variant,
+        solve
+    });
+    header += "} Aoc2021PluginSolver;
+
+/* abi_version is AOC2021_PLUGIN_ABI_VERSION, name prefixes the variant names, name/variant. */
+typedef struct {
+";
+    header += &fields!(PluginInfo {
+        abi_version,
+        name,
+        solvers,
+        solver_count,
+        free
+    });
+    header += "} Aoc2021PluginInfo;
+
+";
+    header += &format!(
+        "{};\n",
+        <EntryPoint as Signature>::declare_function(plugin::ENTRY_POINT, &[])
+    );
+    header += "
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AOC2021_H */
+";
+    header
+}
+
+#[test]
+fn header_in_sync() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/aoc2021.h");
+    if std::env::var_os("AOC2021_UPDATE_HEADER").is_some() {
+        std::fs::create_dir_all(std::path::Path::new(path).parent().unwrap()).unwrap();
+        std::fs::write(path, header()).unwrap();
+    }
+    let current = std::fs::read_to_string(path).unwrap_or_default();
+    assert!(
+        current == header(),
+        "{} is out of date, regenerate it with `AOC2021_UPDATE_HEADER=1 cargo test header_in_sync`",
+        path
+    );
+}
+
+#[test]
+fn solve_reports_errors() {
+    let solve = |day, part, input: &[u8]| unsafe {
+        let mut answer = std::ptr::null_mut();
+        let status = aoc2021_solve(day, part, input.as_ptr(), input.len(), &mut answer);
+        let text = std::ffi::CStr::from_ptr(answer)
+            .to_str()
+            .unwrap()
+            .to_string();
+        aoc2021_free(answer);
+        (status, text)
+    };
+
+    assert_eq!(
+        solve(7, 1, b"16,1,2,0,4,2,7,1,2,14\n"),
+        (OK, String::from("37"))
+    );
+    assert_eq!(solve(7, 3, b"1").0, UNKNOWN_SOLVER);
+    assert_eq!(solve(7, 1, b"\xff").0, INVALID_UTF8);
+    assert_eq!(solve(7, 1, b"no numbers").0, PANICKED);
+
+    let mut answer = std::ptr::null_mut();
+    let status = unsafe { aoc2021_solve(7, 1, std::ptr::null(), 0, &mut answer) };
+    assert_eq!((status, answer), (NULL_ARGUMENT, std::ptr::null_mut()));
+}
@@ -0,0 +1,101 @@
+//! Loads the cdylib the way a C or Python harness would and calls it through `include/aoc2021.h`
+#![cfg(target_os = "linux")]
+
+use aoc2021::plugin;
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+
+const RTLD_NOW: c_int = 2;
+
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlerror() -> *const c_char;
+}
+
+type Solve = unsafe extern "C" fn(u8, u8, *const u8, usize, *mut *mut c_char) -> i32;
+type Free = unsafe extern "C" fn(*mut c_char);
+
+struct Library {
+    solve: Solve,
+    free: Free,
+}
+
+impl Library {
+    /// cargo builds the cdylib into the `deps` directory next to the test binary
+    fn load() -> Self {
+        let exe = std::env::current_exe().unwrap();
+        let path = exe.with_file_name("libaoc2021_ffi.so");
+        let path = CString::new(path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let handle = dlopen(path.as_ptr(), RTLD_NOW);
+            assert!(
+                !handle.is_null(),
+                "{}",
+                CStr::from_ptr(dlerror()).to_string_lossy()
+            );
+            let symbol = |name: &str| {
+                let name = CString::new(name).unwrap();
+                let symbol = dlsym(handle, name.as_ptr());
+                assert!(!symbol.is_null(), "missing symbol {:?}", name);
+                symbol
+            };
+            Library {
+                solve: std::mem::transmute::<*mut c_void, Solve>(symbol("aoc2021_solve")),
+                free: std::mem::transmute::<*mut c_void, Free>(symbol("aoc2021_free")),
+            }
+        }
+    }
+
+    fn solve(&self, day: u8, part: u8, input: &str) -> (i32, String) {
+        unsafe {
+            let mut answer = std::ptr::null_mut();
+            let status = (self.solve)(day, part, input.as_ptr(), input.len(), &mut answer);
+            let text = CStr::from_ptr(answer).to_string_lossy().into_owned();
+            (self.free)(answer);
+            (status, text)
+        }
+    }
+}
+
+#[test]
+fn solves_through_c_abi() {
+    let library = Library::load();
+    let input = include_str!("../../input/day1.example.txt");
+
+    assert_eq!(library.solve(1, 1, input), (0, String::from("7")));
+    assert_eq!(library.solve(1, 2, input), (0, String::from("5")));
+}
+
+#[test]
+fn reports_unknown_solver() {
+    let library = Library::load();
+    let (status, message) = library.solve(26, 1, "");
+
+    assert_eq!(status, 3);
+    assert_eq!(message, "no solver for Day 26 Part 1");
+}
+
+#[test]
+fn null_pointers_handled() {
+    let library = Library::load();
+    unsafe { (library.free)(std::ptr::null_mut()) };
+    let status = unsafe { (library.solve)(1, 1, "".as_ptr(), 0, std::ptr::null_mut()) };
+    assert_eq!(status, 1);
+}
+
+#[test]
+fn rejects_libraries_without_entry_point() {
+    let exe = std::env::current_exe().unwrap();
+    let error = plugin::load(&exe.with_file_name("libaoc2021_ffi.so"))
+        .err()
+        .unwrap();
+
+    assert!(
+        error.ends_with("not a plugin, aoc2021_plugin is missing"),
+        "{}",
+        error
+    );
+}
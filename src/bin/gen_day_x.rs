@@ -1,4 +1,6 @@
+use aoc2021::config::Config;
 use std::io::Write;
+use std::path::Path;
 
 fn main() {
     let day_param = if let Some(first_param) = std::env::args().nth(1) {
@@ -15,56 +17,93 @@ fn main() {
         return;
     };
 
+    // paths and the year come from aoc.toml, see aoc2021::config
+    let current_dir = std::env::current_dir().unwrap();
+    let config = match Config::discover(&current_dir, Path::new(env!("CARGO_MANIFEST_DIR"))) {
+        Ok(config) => config,
+        Err(err) => {
+            eprint!("{}", err);
+            return;
+        }
+    };
+    let src_dir = config.src_dir();
+    let input_dir = config.input_dir();
+    let template = std::fs::read_to_string(config.template_dir().join("lib-mod.rs")).unwrap();
+
     let mut lib_file = std::fs::OpenOptions::new()
         .append(true)
-        .open("src/lib.rs")
+        .open(src_dir.join("lib.rs"))
         .unwrap();
 
     let mut mod_file = std::fs::OpenOptions::new()
         .write(true)
         .create_new(true)
-        .open(&format!("src/day{}.rs", day))
+        .open(src_dir.join(format!("day{}.rs", day)))
         .unwrap();
 
-    let bin_file_path = "src/bin/run.rs";
+    let registry_file_path = src_dir.join("registry.rs");
 
-    let bin_old = std::fs::read_to_string(bin_file_path).unwrap();
+    let registry_old = std::fs::read_to_string(&registry_file_path).unwrap();
 
-    let mut run_bin = std::fs::OpenOptions::new()
+    let mut registry_file = std::fs::OpenOptions::new()
         .write(true)
         .append(false)
-        .open(bin_file_path)
+        .open(registry_file_path)
         .unwrap();
 
     let _example_input = std::fs::OpenOptions::new()
         .write(true)
         .create_new(true)
-        .open(&format!("input/day{}.example.txt", day))
+        .open(input_dir.join(format!("day{}.example.txt", day)))
         .unwrap();
 
     let _input = std::fs::OpenOptions::new()
         .write(true)
         .create_new(true)
-        .open(&format!("input/day{}.txt", day))
+        .open(input_dir.join(format!("day{}.txt", day)))
         .unwrap();
 
+    // the answers go into the sidecars once known, build.rs then generates the tests
+    for expected in [
+        input_dir.join(format!("day{}.example.expected", day)),
+        input_dir.join(format!("day{}.expected", day)),
+    ] {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(expected)
+            .unwrap();
+    }
+
     writeln!(lib_file, "pub mod day{};", day).unwrap();
     write!(
         mod_file,
         "{}",
-        include_str!("../../template/lib-mod.rs")
+        template
+            .replace("YEAR", &config.year().to_string())
+            .replace("DAY", &day.to_string())
             .replace("dayX", &format!("day{}", day))
             .replace("partX", "part1")
     )
     .unwrap();
 
+    let day_marker = "    // gen_day_x inserts new days above this line\n";
+    let solver_marker = "    // gen_day_x inserts new solvers above this line\n";
     write!(
-        run_bin,
+        registry_file,
         "{}",
-        bin_old.replace(
-            "=> default\n",
-            &format!("| day{}\n            => default\n", day)
-        )
+        registry_old
+            .replace(
+                day_marker,
+                &format!("    day!(day{}),\n{}", day, day_marker)
+            )
+            .replace(
+                solver_marker,
+                &format!(
+                    "    solver!(day{0}, part1),\n    solver!(day{0}, part2),\n{1}",
+                    day, solver_marker
+                )
+            )
     )
     .unwrap();
 }
@@ -0,0 +1,75 @@
+use aoc2021::config::parse_duration;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// options that take no value
+const FLAGS: &[&str] = &["strict", "all-profiles", "no-cache"];
+
+/// Command line arguments split into positional arguments, `--name value` options and `--flag`s
+pub struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: HashSet<String>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut positional = vec![];
+        let mut options = HashMap::new();
+        let mut flags = HashSet::new();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if let Some(name) = arg.strip_prefix("--").filter(|name| FLAGS.contains(name)) {
+                flags.insert(name.to_string());
+            } else if let Some(name) = arg.strip_prefix("--") {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --{}", name))?;
+                options.insert(name.to_string(), value);
+            } else {
+                positional.push(arg);
+            }
+        }
+
+        Ok(Args {
+            positional,
+            options,
+            flags,
+        })
+    }
+
+    pub fn positional(&self, idx: usize) -> Option<&str> {
+        self.positional.get(idx).map(String::as_str)
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(name)
+    }
+
+    pub fn parsed_option<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value for --{}: {}", name, value))
+            })
+            .transpose()
+    }
+
+    pub fn timeout(&self) -> Result<Option<Duration>, String> {
+        self.option("timeout")
+            .map(|value| parse_duration(value).ok_or_else(|| format!("Invalid timeout: {}", value)))
+            .transpose()
+    }
+}
+
+/// accepts `day12` as well as `12`
+pub fn parse_day(text: &str) -> Option<u8> {
+    text.strip_prefix("day").unwrap_or(text).parse().ok()
+}
@@ -0,0 +1,152 @@
+use crate::args::{parse_day, Args};
+use crate::perf_history;
+use crate::plugins::{self, Prepared};
+use crate::profile::Profile;
+use crate::{solve_with_timeout, Outcome};
+use aoc2021::budget::Budget;
+use aoc2021::registry;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const DEFAULT_ITERATIONS: usize = 10;
+
+/// median runtime per `(day, part, variant)` of the previous bench run
+pub const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/target/bench-baseline.txt");
+
+pub type Baseline = BTreeMap<(u8, u8, String), Duration>;
+
+pub fn load_baseline(path: &Path) -> Baseline {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let day = fields.next()?.parse().ok()?;
+            let part = fields.next()?.parse().ok()?;
+            let variant = fields.next()?.to_string();
+            let nanos = fields.next()?.parse().ok()?;
+            Some(((day, part, variant), Duration::from_nanos(nanos)))
+        })
+        .collect()
+}
+
+fn save_baseline(path: &Path, baseline: &Baseline) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = baseline
+        .iter()
+        .map(|((day, part, variant), median)| {
+            format!("{} {} {} {}\n", day, part, variant, median.as_nanos())
+        })
+        .collect::<String>();
+    std::fs::write(path, content)
+}
+
+/// Times every variant of the day's parts and compares them with each other and the last run,
+/// the medians are also added to the history shown by `run perf-history`. Plugin variants parse
+/// the input themselves, so their times include parsing.
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let day = args
+        .positional(1)
+        .and_then(parse_day)
+        .ok_or_else(|| crate::USAGE.to_string())?;
+    let parts = match args.positional(2) {
+        Some(part) => vec![part
+            .parse()
+            .map_err(|_| format!("Invalid part: {}", part))?],
+        None => vec![1, 2],
+    };
+    let iterations = args
+        .parsed_option("iterations")?
+        .unwrap_or(DEFAULT_ITERATIONS)
+        .max(1);
+    let timeout = crate::timeout(args, day)?;
+    let input = Profile::from_args(args)?.read_input(day)?;
+
+    // parsing is shared by both parts, so it is timed on its own
+    let mut parse_times = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            let _ = registry::parse(day, &input);
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    parse_times.sort();
+    println!(
+        "Day {} parse           : median {:>12?} min {:>12?}",
+        day,
+        parse_times[parse_times.len() / 2],
+        parse_times[0]
+    );
+    let prepared = Prepared::new(day, &input)?;
+
+    let baseline_path = Path::new(BASELINE_PATH);
+    let mut baseline = load_baseline(baseline_path);
+    let mut history = vec![];
+
+    for part in parts {
+        let mut medians = vec![];
+
+        for solver in plugins::variants(day, part) {
+            // the warm up run also makes sure the solver finishes within the timeout
+            match solve_with_timeout(solver, &prepared, timeout) {
+                Outcome::Answer(_) => {}
+                outcome => {
+                    println!(
+                        "Day {} Part {} {:>10}: {}",
+                        day,
+                        part,
+                        solver.variant(),
+                        outcome
+                    );
+                    continue;
+                }
+            }
+
+            let mut times = (0..iterations)
+                .map(|_| {
+                    let start = Instant::now();
+                    let _ = solver.solve(&prepared, &Budget::unlimited());
+                    start.elapsed()
+                })
+                .collect::<Vec<_>>();
+            times.sort();
+            medians.push((solver, times[0], times[times.len() / 2]));
+        }
+
+        let fastest = medians.iter().map(|(_, _, median)| *median).min();
+
+        for (solver, min, median) in medians {
+            let key = (day, part, solver.variant().to_string());
+            let relative = median.as_secs_f64() / fastest.unwrap().as_secs_f64();
+            let change = match baseline.get(&key) {
+                Some(last) => format!(
+                    "{:+.1}% vs last run",
+                    (median.as_secs_f64() / last.as_secs_f64() - 1.0) * 100.0
+                ),
+                None => String::from("no previous run"),
+            };
+            println!(
+                "Day {} Part {} {:>10}: median {:>12?} min {:>12?} x{:.2} {}",
+                day,
+                part,
+                solver.variant(),
+                median,
+                min,
+                relative,
+                change
+            );
+            baseline.insert(key, median);
+            history.push((day, part, solver.variant(), median));
+        }
+    }
+
+    save_baseline(baseline_path, &baseline)
+        .map_err(|err| format!("{}: {}", baseline_path.display(), err))?;
+    perf_history::append(&history)?;
+
+    Ok(ExitCode::SUCCESS)
+}
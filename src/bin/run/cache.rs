@@ -0,0 +1,163 @@
+//! Answers cached on disk, so slow parts are not recomputed on every run
+//!
+//! An answer is keyed by day, part, variant, the hash of the normalized input and the hash of the
+//! day's source with the shared library modules, see [`registry::source_hash`]. Changing either
+//! computes it again. The cache also remembers the last input hash of every input file to point
+//! out swapped inputs.
+//!
+//! One tab separated record per line in `.answer-cache` next to `aoc.toml`:
+//!
+//! ```text
+//! answer  7  1  default  <input hash>  <source hash>  37
+//! input   input/day7.txt  <input hash>
+//! ```
+
+use crate::config;
+use aoc2021::registry::{self, Solver};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = ".answer-cache";
+
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Key {
+    day: u8,
+    part: u8,
+    variant: String,
+    input_hash: u64,
+    source_hash: u64,
+}
+
+pub struct Cache {
+    path: PathBuf,
+    answers: BTreeMap<Key, String>,
+    /// the input hash seen last for each input file
+    inputs: BTreeMap<String, u64>,
+}
+
+/// multi-line answers are stored on one line
+fn escape(answer: &str) -> String {
+    answer.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn unescape(answer: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = answer.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn parse_hash(hash: &str) -> Option<u64> {
+    u64::from_str_radix(hash, 16).ok()
+}
+
+impl Cache {
+    /// The cache of the project, a missing or damaged cache file is an empty cache
+    pub fn load() -> Self {
+        let path = config().root.join(FILE_NAME);
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let mut cache = Cache {
+            path,
+            answers: BTreeMap::new(),
+            inputs: BTreeMap::new(),
+        };
+
+        for line in content.lines() {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            match fields[..] {
+                ["answer", day, part, variant, input_hash, source_hash, answer] => {
+                    let key = (|| {
+                        Some(Key {
+                            day: day.parse().ok()?,
+                            part: part.parse().ok()?,
+                            variant: variant.to_string(),
+                            input_hash: parse_hash(input_hash)?,
+                            source_hash: parse_hash(source_hash)?,
+                        })
+                    })();
+                    if let Some(key) = key {
+                        cache.answers.insert(key, unescape(answer));
+                    }
+                }
+                ["input", path, hash] => {
+                    if let Some(hash) = parse_hash(hash) {
+                        cache.inputs.insert(path.to_string(), hash);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        cache
+    }
+
+    fn key(solver: &Solver, input_hash: u64) -> Key {
+        Key {
+            day: solver.day,
+            part: solver.part,
+            variant: solver.variant.to_string(),
+            input_hash,
+            source_hash: registry::source_hash(solver.day).unwrap_or_default(),
+        }
+    }
+
+    pub fn get(&self, solver: &Solver, input_hash: u64) -> Option<&str> {
+        self.answers
+            .get(&Cache::key(solver, input_hash))
+            .map(String::as_str)
+    }
+
+    /// Stores the answer, replacing answers of older versions of the solver for the same input
+    pub fn insert(&mut self, solver: &Solver, input_hash: u64, answer: &str) {
+        let key = Cache::key(solver, input_hash);
+        self.answers.retain(|cached, _| {
+            (cached.day, cached.part, &cached.variant, cached.input_hash)
+                != (key.day, key.part, &key.variant, key.input_hash)
+        });
+        self.answers.insert(key, answer.to_string());
+    }
+
+    /// Records the input file's hash, returns the previous one if the content has changed
+    pub fn check_input(&mut self, path: &Path, input_hash: u64) -> Option<u64> {
+        let path = path
+            .strip_prefix(&config().root)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        self.inputs
+            .insert(path, input_hash)
+            .filter(|&previous| previous != input_hash)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let mut content = String::new();
+        for (key, answer) in &self.answers {
+            content += &format!(
+                "answer\t{}\t{}\t{}\t{:016x}\t{:016x}\t{}\n",
+                key.day,
+                key.part,
+                key.variant,
+                key.input_hash,
+                key.source_hash,
+                escape(answer)
+            );
+        }
+        for (path, hash) in &self.inputs {
+            content += &format!("input\t{}\t{:016x}\n", path, hash);
+        }
+        std::fs::write(&self.path, content)
+            .map_err(|err| format!("{}: {}", self.path.display(), err))
+    }
+}
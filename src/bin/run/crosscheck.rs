@@ -0,0 +1,106 @@
+use crate::args::{parse_day, Args};
+use crate::plugins::{self, Prepared};
+use crate::profile::Profile;
+use crate::{solve_with_timeout, Outcome};
+use aoc2021::registry;
+use aoc2021::rng::Rng;
+use std::process::ExitCode;
+
+const DEFAULT_SIZE: usize = 100;
+
+/// Runs every variant of a part, built-in or from a plugin, on the same inputs and reports
+/// disagreements
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let day = args
+        .positional(1)
+        .and_then(parse_day)
+        .ok_or_else(|| crate::USAGE.to_string())?;
+    let parts = match args.positional(2) {
+        Some(part) => vec![part
+            .parse()
+            .map_err(|_| format!("Invalid part: {}", part))?],
+        None => vec![1, 2],
+    };
+    let timeout = crate::timeout(args, day)?;
+    let random = args.parsed_option::<u64>("random")?;
+    let size = args.parsed_option("size")?.unwrap_or(DEFAULT_SIZE);
+    let seed = args.parsed_option("seed")?.unwrap_or(0);
+
+    let inputs = match random {
+        Some(count) => {
+            let generator = registry::generator(day)
+                .ok_or_else(|| format!("No input generator for day {}", day))?;
+            (seed..seed + count)
+                .map(|seed| {
+                    let input = (generator.generate)(&mut Rng::new(seed), size);
+                    (
+                        format!("random input (--seed {} --size {})", seed, size),
+                        input,
+                    )
+                })
+                .collect::<Vec<_>>()
+        }
+        None => {
+            let profile = Profile::from_args(args)?;
+            vec![(
+                profile.input_path(day).display().to_string(),
+                profile.read_input(day)?,
+            )]
+        }
+    };
+    // every variant of both parts gets the same parsed input
+    let inputs = inputs
+        .into_iter()
+        .map(|(name, input)| Ok((name, Prepared::new(day, &input)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut disagreements = 0;
+
+    for part in parts {
+        let variants = plugins::variants(day, part);
+        if variants.len() < 2 {
+            println!(
+                "Day {} Part {}: only one variant, nothing to compare",
+                day, part
+            );
+            continue;
+        }
+
+        for (name, prepared) in &inputs {
+            let outcomes = variants
+                .iter()
+                .map(|&solver| solve_with_timeout(solver, prepared, timeout))
+                .collect::<Vec<_>>();
+
+            let agree = match &outcomes[0] {
+                Outcome::Answer(first) => outcomes
+                    .iter()
+                    .all(|outcome| matches!(outcome, Outcome::Answer(answer) if answer == first)),
+                Outcome::Timeout | Outcome::Panicked | Outcome::Failed(_) => false,
+            };
+
+            if !agree {
+                disagreements += 1;
+                println!("Day {} Part {}: disagreement on {}", day, part, name);
+                for (solver, outcome) in variants.iter().zip(&outcomes) {
+                    println!("    {:>10}: {}", solver.variant(), outcome);
+                }
+            }
+        }
+
+        println!(
+            "Day {} Part {}: checked {} variants on {} inputs",
+            day,
+            part,
+            variants.len(),
+            inputs.len()
+        );
+    }
+
+    if disagreements == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("{} disagreements", disagreements);
+        Ok(ExitCode::FAILURE)
+    }
+}
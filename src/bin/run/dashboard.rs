@@ -0,0 +1,434 @@
+//! `run dashboard`, a full-screen calendar of the 25 days
+//!
+//! Every day is colored by how many of its parts match the expected answers of the profile's
+//! full input, a day whose run from the dashboard timed out is marked `..`. Answers come from the answer cache until the day is run from the dashboard, the
+//! timing of the last run is compared with the median saved by `run bench`.
+//!
+//! The terminal is switched to non-canonical mode with `stty`, so this needs a Unix terminal.
+
+use crate::args::Args;
+use crate::bench::{load_baseline, BASELINE_PATH};
+use crate::cache::Cache;
+use crate::plugins::{AnySolver, Prepared};
+use crate::profile::Profile;
+use crate::{parse_ahead, solve_with_timeout, Outcome};
+use aoc2021::hash::fnv1a;
+use aoc2021::input;
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, ExitCode, Stdio};
+use std::time::{Duration, Instant};
+
+const DAYS: u8 = 25;
+const COLUMNS: u8 = 5;
+
+/// changes within this many percent of the baseline are not a trend
+const TREND_THRESHOLD: f64 = 5.0;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+struct Part {
+    expected: Option<String>,
+    answer: Option<String>,
+    /// the run from the dashboard ran out of time, there is no answer
+    timed_out: bool,
+    /// the time of the run from the dashboard, cached answers have none
+    elapsed: Option<Duration>,
+    baseline: Option<Duration>,
+}
+
+impl Part {
+    /// `Some(true)` if the answer matches, `None` if either is unknown
+    fn verified(&self) -> Option<bool> {
+        let expected = self.expected.as_ref()?;
+        let answer = self.answer.as_ref()?;
+        Some(expected.trim_end() == answer.trim_end())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Missing,
+    Unverified,
+    OnePart,
+    BothParts,
+    TimedOut,
+    Failing,
+}
+
+struct Day {
+    day: u8,
+    implemented: bool,
+    parts: [Part; 2],
+}
+
+impl Day {
+    fn status(&self) -> Status {
+        let verified = self.parts.iter().map(Part::verified).collect::<Vec<_>>();
+        let passed = verified.iter().filter(|&&part| part == Some(true)).count();
+        // the last day has a single puzzle, its second star is free
+        let needed = if self.day == DAYS { 1 } else { 2 };
+        if !self.implemented {
+            Status::Missing
+        } else if verified.contains(&Some(false)) {
+            Status::Failing
+        } else if self.parts.iter().any(|part| part.timed_out) {
+            Status::TimedOut
+        } else if passed >= needed {
+            Status::BothParts
+        } else if passed > 0 {
+            Status::OnePart
+        } else {
+            Status::Unverified
+        }
+    }
+
+    /// the time of both parts, from the last run or else the bench baseline
+    fn timing(&self) -> Option<(Duration, Option<f64>)> {
+        let sum = |times: Vec<Option<Duration>>| times.into_iter().sum::<Option<Duration>>();
+        let elapsed = sum(self.parts.iter().map(|part| part.elapsed).collect());
+        let baseline = sum(self.parts.iter().map(|part| part.baseline).collect());
+        match (elapsed, baseline) {
+            (Some(elapsed), Some(baseline)) => Some((
+                elapsed,
+                Some((elapsed.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0),
+            )),
+            (Some(elapsed), None) => Some((elapsed, None)),
+            (None, baseline) => baseline.map(|baseline| (baseline, None)),
+        }
+    }
+}
+
+struct Dashboard {
+    profile: Profile,
+    cache: Cache,
+    days: Vec<Day>,
+    selected: u8,
+    message: String,
+}
+
+impl Dashboard {
+    fn load(profile: Profile) -> Self {
+        let cache = Cache::load();
+        let baseline = load_baseline(Path::new(BASELINE_PATH));
+        let days = (1..=DAYS)
+            .map(|day| {
+                let expected = profile
+                    .input(day, "full")
+                    .ok()
+                    .and_then(|input| input.expected)
+                    .unwrap_or_default();
+                let input_hash = profile
+                    .read_input(day)
+                    .ok()
+                    .map(|input| fnv1a(input::normalize(&input).as_bytes()));
+                let parts = [1, 2].map(|part| {
+                    let solver = registry::solver(day, part, DEFAULT_VARIANT);
+                    Part {
+                        expected: expected.answers.get(&part).cloned(),
+                        answer: solver
+                            .zip(input_hash)
+                            .and_then(|(solver, hash)| cache.get(solver, hash))
+                            .map(str::to_string),
+                        timed_out: false,
+                        elapsed: None,
+                        baseline: baseline
+                            .get(&(day, part, DEFAULT_VARIANT.to_string()))
+                            .copied(),
+                    }
+                });
+                Day {
+                    day,
+                    implemented: registry::DAYS.iter().any(|entry| entry.day == day),
+                    parts,
+                }
+            })
+            .collect();
+
+        Dashboard {
+            profile,
+            cache,
+            days,
+            selected: 1,
+            message: String::new(),
+        }
+    }
+
+    fn select(&mut self, offset: i8) {
+        let day = self.selected as i8 + offset;
+        if (1..=DAYS as i8).contains(&day) {
+            self.selected = day as u8;
+        }
+    }
+
+    /// Solves both parts of the selected day, timing them and storing the answers in the cache
+    fn run_selected(&mut self, timeout: Option<Duration>) -> Result<(), String> {
+        let day = self.selected;
+        let input = self.profile.read_input(day)?;
+        let input_hash = fnv1a(input::normalize(&input).as_bytes());
+        let prepared = Prepared::new(day, &input)?;
+        parse_ahead(&prepared, timeout);
+
+        for part in 1..=2 {
+            let solver = match registry::solver(day, part, DEFAULT_VARIANT) {
+                Some(solver) => solver,
+                None => continue,
+            };
+            let start = Instant::now();
+            let outcome = solve_with_timeout(AnySolver::Builtin(solver), &prepared, timeout);
+            let elapsed = start.elapsed();
+            if let Outcome::Answer(answer) = &outcome {
+                self.cache.insert(solver, input_hash, answer);
+            }
+            let state = &mut self.days[day as usize - 1].parts[part as usize - 1];
+            state.elapsed = Some(elapsed);
+            state.timed_out = matches!(outcome, Outcome::Timeout);
+            state.answer = (!state.timed_out).then(|| outcome.to_string());
+        }
+        Ok(())
+    }
+
+    fn cell(&self, day: &Day) -> String {
+        let color = match day.status() {
+            Status::Missing => DIM,
+            Status::Unverified => "",
+            Status::OnePart => YELLOW,
+            Status::BothParts => GREEN,
+            Status::TimedOut => YELLOW,
+            Status::Failing => RED,
+        };
+        let stars = match day.status() {
+            Status::BothParts => "**",
+            Status::OnePart => "* ",
+            Status::TimedOut => "..",
+            Status::Failing => "!!",
+            Status::Missing | Status::Unverified => "  ",
+        };
+        let (time, trend) = match day.timing() {
+            Some((time, Some(change))) if change > TREND_THRESHOLD => (
+                format_time(time),
+                format!("{}▲{:>4.0}%{}", RED, change, RESET),
+            ),
+            Some((time, Some(change))) if change < -TREND_THRESHOLD => (
+                format_time(time),
+                format!("{}▼{:>4.0}%{}", GREEN, -change, RESET),
+            ),
+            Some((time, Some(_))) => (format_time(time), String::from("   =  ")),
+            Some((time, None)) => (format_time(time), String::from("      ")),
+            None => (String::new(), String::from("      ")),
+        };
+        let selected = if day.day == self.selected {
+            REVERSE
+        } else {
+            ""
+        };
+        format!(
+            "{}{}{:>2} {} {:>8}{} {}",
+            selected, color, day.day, stars, time, RESET, trend
+        )
+    }
+
+    fn render(&self) -> String {
+        let mut screen = String::from(CLEAR_SCREEN);
+        screen += &format!(
+            "{}Advent of Code {}{}, profile {}\r\n\r\n",
+            BOLD,
+            crate::config().year(),
+            RESET,
+            self.profile.name()
+        );
+        for row in 0..DAYS / COLUMNS {
+            let cells = (0..COLUMNS)
+                .map(|column| self.cell(&self.days[(row * COLUMNS + column) as usize]))
+                .collect::<Vec<_>>();
+            screen += &format!("  {}\r\n", cells.join("   "));
+        }
+
+        let day = &self.days[self.selected as usize - 1];
+        screen += &format!("\r\n{}Day {}{}\r\n", BOLD, day.day, RESET);
+        for (part, state) in day.parts.iter().enumerate() {
+            let (color, result) = match state.verified() {
+                None if state.timed_out => (YELLOW, "timeout"),
+                Some(true) => (GREEN, "pass"),
+                Some(false) => (RED, "FAIL"),
+                None if state.expected.is_none() => ("", "no expected answer"),
+                None => ("", "not run"),
+            };
+            let answer = state.answer.as_deref().unwrap_or("-");
+            // multi-line answers only show their first line
+            let answer = match answer.trim_end().split_once('\n') {
+                Some((first, _)) => format!("{} ...", first),
+                None => answer.trim_end().to_string(),
+            };
+            let elapsed = state.elapsed.map(format_time).unwrap_or_default();
+            let baseline = state
+                .baseline
+                .map(|baseline| format!("bench {}", format_time(baseline)))
+                .unwrap_or_default();
+            screen += &format!(
+                "  Part {}: {:<24} {}{:<20}{} {:>8}  {}\r\n",
+                part + 1,
+                answer,
+                color,
+                result,
+                RESET,
+                elapsed,
+                baseline
+            );
+        }
+
+        screen += &format!("\r\n{}\r\n", self.message);
+        screen += &format!(
+            "{}arrows or hjkl select, enter runs the day, q quits{}",
+            DIM, RESET
+        );
+        screen
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs_f64();
+    if seconds >= 1.0 {
+        format!("{:.2}s", seconds)
+    } else if seconds >= 1e-3 {
+        format!("{:.1}ms", seconds * 1e3)
+    } else {
+        format!("{:.0}µs", seconds * 1e6)
+    }
+}
+
+/// Non-canonical input without echo on the alternate screen, restored when dropped. Keys are
+/// read from `tty`, the terminal that `stty` configures, whatever stdin is.
+struct Terminal {
+    settings: String,
+    tty: File,
+}
+
+fn open_tty() -> Result<File, String> {
+    File::open("/dev/tty").map_err(|err| format!("/dev/tty: {}", err))
+}
+
+fn stty(args: &[&str]) -> Result<String, String> {
+    let tty = open_tty()?;
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(tty)
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|err| format!("Failed to run stty: {}", err))?;
+    if !output.status.success() {
+        return Err(String::from("run dashboard needs a terminal"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+impl Terminal {
+    fn enter() -> Result<Self, String> {
+        let settings = stty(&["-g"])?;
+        // reads give up after a tenth of a second, so a lone Esc isn't mistaken for the start
+        // of an arrow key's escape sequence
+        stty(&["-icanon", "-echo", "min", "0", "time", "1"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        Ok(Terminal {
+            settings,
+            tty: open_tty()?,
+        })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = std::io::stdout().flush();
+        let _ = stty(&[&self.settings]);
+    }
+}
+
+enum Key {
+    Left,
+    Right,
+    Up,
+    Down,
+    Enter,
+    Quit,
+    Other,
+}
+
+/// the next byte, or `None` if none came before the read timed out
+fn read_byte(tty: &mut impl Read) -> std::io::Result<Option<u8>> {
+    let mut byte = [0];
+    Ok((tty.read(&mut byte)? == 1).then_some(byte[0]))
+}
+
+fn read_key(tty: &mut impl Read) -> Key {
+    let first = loop {
+        let start = Instant::now();
+        match read_byte(tty) {
+            Ok(Some(byte)) => break byte,
+            // a read that times out waits a tenth of a second, one that returns nothing at once
+            // is at the end of the input, e.g. after the terminal hung up
+            Ok(None) if start.elapsed() >= Duration::from_millis(50) => continue,
+            Ok(None) | Err(_) => return Key::Quit,
+        }
+    };
+    match first {
+        // the terminal sends an arrow key's sequence at once, nothing follows a lone Esc
+        b'\x1b' if matches!(read_byte(tty), Ok(Some(b'['))) => match read_byte(tty) {
+            Ok(Some(b'A')) => Key::Up,
+            Ok(Some(b'B')) => Key::Down,
+            Ok(Some(b'C')) => Key::Right,
+            Ok(Some(b'D')) => Key::Left,
+            _ => Key::Other,
+        },
+        b'k' => Key::Up,
+        b'j' => Key::Down,
+        b'l' => Key::Right,
+        b'h' => Key::Left,
+        b'\n' | b'\r' | b' ' => Key::Enter,
+        b'q' | b'\x04' => Key::Quit,
+        _ => Key::Other,
+    }
+}
+
+/// Shows the calendar until `q` is pressed
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let mut dashboard = Dashboard::load(Profile::from_args(args)?);
+    let mut terminal = Terminal::enter()?;
+    let mut stdout = std::io::stdout();
+
+    loop {
+        print!("{}", dashboard.render());
+        stdout.flush().map_err(|err| err.to_string())?;
+        match read_key(&mut terminal.tty) {
+            Key::Left => dashboard.select(-1),
+            Key::Right => dashboard.select(1),
+            Key::Up => dashboard.select(-(COLUMNS as i8)),
+            Key::Down => dashboard.select(COLUMNS as i8),
+            Key::Enter => {
+                let day = dashboard.selected;
+                dashboard.message = format!("Running day {}...", day);
+                print!("{}", dashboard.render());
+                stdout.flush().map_err(|err| err.to_string())?;
+                dashboard.message = match dashboard.run_selected(crate::timeout(args, day)?) {
+                    Ok(()) => format!("Ran day {}", day),
+                    Err(err) => err,
+                };
+            }
+            Key::Quit => break,
+            Key::Other => {}
+        }
+    }
+
+    drop(terminal);
+    dashboard.cache.save()?;
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,347 @@
+//! Cross-checks the places a day has to be added to, `gen_day_x` edits them with plain string
+//! replacement and nothing else notices when one of them is missed
+
+use crate::args::Args;
+use crate::config;
+use crate::profile::{Profile, ANSWERS_FILE};
+use aoc2021::expected::{self, Expected};
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use aoc2021::seal::{self, SealError};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// An inconsistency and how to resolve it
+struct Problem {
+    problem: String,
+    fix: String,
+}
+
+#[derive(Default)]
+struct Doctor {
+    problems: Vec<Problem>,
+}
+
+impl Doctor {
+    fn report(&mut self, problem: String, fix: String) {
+        self.problems.push(Problem { problem, fix });
+    }
+}
+
+/// the path relative to the project root, for shorter messages
+fn show(path: &Path) -> String {
+    path.strip_prefix(&config().root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// the day of `dayN` followed by `suffix`, e.g. 12 for `day12.rs` with suffix `.rs`
+fn day_of(name: &str, suffix: &str) -> Option<u8> {
+    name.strip_prefix("day")?.strip_suffix(suffix)?.parse().ok()
+}
+
+/// the names of the files in `dir`, sorted
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn registered(day: u8) -> bool {
+    registry::DAYS
+        .iter()
+        .any(|registered| registered.day == day)
+}
+
+/// Reports every inconsistency with a suggested fix, fails if there is any
+pub fn main(_args: &Args) -> Result<ExitCode, String> {
+    let mut doctor = Doctor::default();
+    check_modules(&mut doctor);
+    check_inputs(&mut doctor);
+    for profile in Profile::all().iter().skip(1) {
+        check_profile(&mut doctor, profile);
+    }
+
+    for Problem { problem, fix } in &doctor.problems {
+        println!("{}\n    fix: {}", problem, fix);
+    }
+    match doctor.problems.len() {
+        0 => {
+            println!("No problems found");
+            Ok(ExitCode::SUCCESS)
+        }
+        1 => {
+            println!("1 problem found");
+            Ok(ExitCode::FAILURE)
+        }
+        count => {
+            println!("{} problems found", count);
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// `src/dayN.rs`, the `pub mod dayN;` lines in `src/lib.rs` and the registry agree
+fn check_modules(doctor: &mut Doctor) {
+    let src_dir = config().src_dir();
+    let lib_path = src_dir.join("lib.rs");
+    let registry_path = src_dir.join("registry.rs");
+    let lib = std::fs::read_to_string(&lib_path).unwrap_or_default();
+
+    let files = file_names(&src_dir)
+        .iter()
+        .filter_map(|name| day_of(name, ".rs"))
+        .collect::<BTreeSet<_>>();
+    let modules = lib
+        .lines()
+        .filter_map(|line| day_of(line.trim().strip_prefix("pub mod ")?, ";"))
+        .collect::<BTreeSet<_>>();
+
+    for &day in files.difference(&modules) {
+        doctor.report(
+            format!("src/day{}.rs is not a module", day),
+            format!("add `pub mod day{};` to {}", day, show(&lib_path)),
+        );
+    }
+    for &day in modules.difference(&files) {
+        doctor.report(
+            format!(
+                "{} declares day{} but there is no src/day{}.rs",
+                show(&lib_path),
+                day,
+                day
+            ),
+            format!("remove `pub mod day{};` or restore src/day{}.rs", day, day),
+        );
+    }
+    for &day in files.intersection(&modules) {
+        if !registered(day) {
+            doctor.report(
+                format!("day{} is not in the registry", day),
+                format!(
+                    "add `day!(day{}),` to DAYS in {}",
+                    day,
+                    show(&registry_path)
+                ),
+            );
+            continue;
+        }
+        for part in 1..=2 {
+            if registry::solver(day, part, DEFAULT_VARIANT).is_none() {
+                doctor.report(
+                    format!("day{} part{} has no solver", day, part),
+                    format!(
+                        "add `solver!(day{}, part{}),` to SOLVERS in {}",
+                        day,
+                        part,
+                        show(&registry_path)
+                    ),
+                );
+            }
+        }
+    }
+    for day in registry::DAYS.iter().map(|day| day.day) {
+        if !modules.contains(&day) {
+            doctor.report(
+                format!("day{} is registered but not in {}", day, show(&lib_path)),
+                format!("add `pub mod day{};` to {}", day, show(&lib_path)),
+            );
+        }
+    }
+}
+
+/// the default profile: every day has an input, every input has a day and is tested, either by
+/// a `.expected` sidecar or by a test in the day's module
+fn check_inputs(doctor: &mut Doctor) {
+    let input_dir = config().input_dir();
+    let names = file_names(&input_dir);
+
+    for day in registry::DAYS.iter().map(|day| day.day) {
+        if !names.contains(&format!("day{}.txt", day)) {
+            doctor.report(
+                format!("day{} has no input", day),
+                format!(
+                    "save your puzzle input as {}",
+                    show(&input_dir.join(format!("day{}.txt", day)))
+                ),
+            );
+        }
+    }
+
+    for name in &names {
+        let path = input_dir.join(name);
+        if let Some(stem) = name.strip_suffix(".txt") {
+            let Some(day) = day_of(stem.split('.').next().unwrap_or_default(), "") else {
+                continue;
+            };
+            if !registered(day) {
+                doctor.report(
+                    format!(
+                        "{} belongs to day{}, which does not exist",
+                        show(&path),
+                        day
+                    ),
+                    format!(
+                        "add the day with `cargo run --bin gen_day_x {}` or remove the input",
+                        day
+                    ),
+                );
+                continue;
+            }
+            check_sealed(doctor, &path);
+
+            let expected_path = path.with_extension("expected");
+            if !expected_path.exists() && !referenced_by_tests(day, name) {
+                doctor.report(
+                    format!("{} is not tested", show(&path)),
+                    format!(
+                        "add the known answers to {}, build.rs generates the tests from it",
+                        show(&expected_path)
+                    ),
+                );
+            }
+        } else if let Some(stem) = name.strip_suffix(".expected") {
+            let Some(day) = day_of(stem.split('.').next().unwrap_or_default(), "") else {
+                continue;
+            };
+            if !names.contains(&format!("{}.txt", stem)) {
+                doctor.report(
+                    format!("{} has no input", show(&path)),
+                    format!("add {}.txt or remove the sidecar", stem),
+                );
+            }
+            if let Some(expected) = read_expected(doctor, &path) {
+                check_expected(doctor, &show(&path), day, &expected);
+            }
+        }
+    }
+}
+
+/// tests in `src/dayN.rs` that `include_str!` the input
+fn referenced_by_tests(day: u8, name: &str) -> bool {
+    std::fs::read_to_string(config().src_dir().join(format!("day{}.rs", day)))
+        .is_ok_and(|source| source.contains(&format!("/{}\"", name)))
+}
+
+fn check_sealed(doctor: &mut Doctor, path: &Path) {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    match seal::open(&content, &config().key_path()) {
+        Ok(_) => {}
+        Err(SealError::MissingKey) => doctor.report(
+            format!("{} is sealed but there is no key", show(path)),
+            format!(
+                "set {} or create {}",
+                seal::KEY_VARIABLE,
+                config().key_path().display()
+            ),
+        ),
+        Err(err) => doctor.report(
+            format!("{}: {}", show(path), err),
+            String::from("restore the input, or unseal it with the key it was sealed with"),
+        ),
+    }
+}
+
+fn read_expected(doctor: &mut Doctor, path: &Path) -> Option<Expected> {
+    let content = std::fs::read_to_string(path).unwrap_or_default();
+    match expected::parse(&content) {
+        Ok(expected) => Some(expected),
+        Err(err) => {
+            doctor.report(
+                format!("{}: {}", show(path), err),
+                String::from("fix the line, the format is described in src/expected.rs"),
+            );
+            None
+        }
+    }
+}
+
+/// there are answers, and only for parts that exist
+fn check_expected(doctor: &mut Doctor, name: &str, day: u8, expected: &Expected) {
+    if expected.answers.is_empty() && expected.ignore.is_none() {
+        doctor.report(
+            format!("{} has no answers", name),
+            String::from("add `part1: <answer>` lines once the answers are known"),
+        );
+    }
+    for &part in expected.answers.keys() {
+        if registry::solver(day, part, DEFAULT_VARIANT).is_none() {
+            doctor.report(
+                format!(
+                    "{} has an answer for day{} part{}, which has no solver",
+                    name, day, part
+                ),
+                format!("remove `part{}:` or add the solver", part),
+            );
+        }
+    }
+}
+
+/// a named profile: its inputs and its answers file agree
+fn check_profile(doctor: &mut Doctor, profile: &Profile) {
+    let answers_path = profile.answers_path();
+    let answers = match std::fs::read_to_string(&answers_path) {
+        Ok(content) => match expected::parse_sections(&content) {
+            Ok(answers) => answers,
+            Err(err) => {
+                doctor.report(
+                    format!("{}: {}", show(&answers_path), err),
+                    String::from("fix the line, each day's answers follow a [dayN] line"),
+                );
+                return;
+            }
+        },
+        Err(_) => BTreeMap::new(),
+    };
+
+    let mut inputs = BTreeMap::<u8, PathBuf>::new();
+    for name in file_names(&profile.dir()) {
+        let path = profile.dir().join(&name);
+        match day_of(&name, ".txt") {
+            Some(day) if registered(day) => {
+                check_sealed(doctor, &path);
+                inputs.insert(day, path);
+            }
+            Some(day) => doctor.report(
+                format!(
+                    "{} belongs to day{}, which does not exist",
+                    show(&path),
+                    day
+                ),
+                String::from("remove the input"),
+            ),
+            None if name == ANSWERS_FILE => {}
+            None => doctor.report(
+                format!("{} is not an input of the profile", show(&path)),
+                format!("profiles only hold dayN.txt inputs and {}", ANSWERS_FILE),
+            ),
+        }
+    }
+
+    for (day, path) in &inputs {
+        if !answers.contains_key(day) {
+            doctor.report(
+                format!("{} is not tested", show(path)),
+                format!("add a [day{}] section to {}", day, show(&answers_path)),
+            );
+        }
+    }
+    for (day, expected) in &answers {
+        let name = format!("[day{}] in {}", day, show(&answers_path));
+        if !inputs.contains_key(day) {
+            doctor.report(
+                format!("{} has no input", name),
+                format!(
+                    "add {} or remove the section",
+                    show(&profile.input_path(*day))
+                ),
+            );
+        }
+        check_expected(doctor, &name, *day, expected);
+    }
+}
@@ -0,0 +1,43 @@
+use crate::args::{parse_day, Args};
+use crate::profile::Profile;
+use crate::read_file;
+use aoc2021::json::{self, Value};
+use aoc2021::registry;
+use std::process::ExitCode;
+
+/// Prints a day's explain report as JSON, for the full input or e.g. `--input example`
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let usage = "Usage: run explain <day> <part> [--input name] [--profile name]";
+    let day = args.positional(1).ok_or(usage)?;
+    let day = parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?;
+    let part = match args.positional(2).map(str::parse) {
+        Some(Ok(part @ 1..=2)) => part,
+        _ => return Err(usage.to_string()),
+    };
+    let explainer = registry::explainer(day).ok_or_else(|| {
+        let days = registry::EXPLAINERS
+            .iter()
+            .map(|explainer| explainer.day.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "Day {} has no explain hook, days with one: {}",
+            day,
+            days.join(", ")
+        )
+    })?;
+
+    let profile = Profile::from_args(args)?;
+    let name = args.option("input").unwrap_or("full");
+    let input = profile.input(day, name)?;
+    let parsed = registry::parse(day, &read_file(&input.path)?)
+        .ok_or_else(|| format!("Unknown day: {}", day))?;
+
+    let report = json::object([
+        ("day", day.into()),
+        ("part", part.into()),
+        ("input", Value::from(input.path.display().to_string())),
+        ("report", (explainer.explain)(&*parsed, part)),
+    ]);
+    println!("{}", report);
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,138 @@
+use crate::args::Args;
+use crate::read_file;
+use aoc2021::leaderboard::{self, format_duration, Leaderboard, Scoring};
+use std::path::Path;
+use std::process::ExitCode;
+
+const REPORTS: &[&str] = &["rankings", "timeline", "deltas", "compare"];
+
+/// Reports on a saved private leaderboard, `--member` defaults to the leaderboard's owner
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let (report, path) = match (args.positional(1), args.positional(2)) {
+        (Some(report), Some(path)) if REPORTS.contains(&report) => (report, path),
+        (Some(path), None) => ("rankings", path),
+        _ => {
+            return Err(format!(
+                "Usage: run leaderboard [{}] <file>",
+                REPORTS.join("|")
+            ))
+        }
+    };
+    let leaderboard = leaderboard::parse(&read_file(Path::new(path))?)
+        .map_err(|err| format!("{}: {}", path, err))?;
+    let member = args
+        .parsed_option("member")?
+        .unwrap_or(leaderboard.owner_id);
+
+    match report {
+        "rankings" => rankings(&leaderboard, args)?,
+        "timeline" => timeline(&leaderboard),
+        "deltas" => deltas(&leaderboard),
+        _ => compare(&leaderboard, member)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn score(scoring: Scoring, score: i64) -> String {
+    if scoring.is_duration() {
+        format_duration(score)
+    } else {
+        score.to_string()
+    }
+}
+
+/// every scoring, or only the one of `--scoring`
+fn rankings(leaderboard: &Leaderboard, args: &Args) -> Result<(), String> {
+    let scorings = match args.option("scoring") {
+        Some(name) => vec![Scoring::from_name(name).ok_or_else(|| {
+            let names = Scoring::ALL.map(Scoring::name);
+            format!("Unknown scoring {}, expected one of {:?}", name, names)
+        })?],
+        None => Scoring::ALL.to_vec(),
+    };
+
+    for (idx, &scoring) in scorings.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        println!("by {}:", scoring.name());
+        for (rank, (member, points)) in leaderboard::ranking(leaderboard, scoring)
+            .into_iter()
+            .enumerate()
+        {
+            println!(
+                "{:>4}) {:>12} {:>3}* {}",
+                rank + 1,
+                score(scoring, points),
+                member.stars,
+                member.display_name()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// every member's stars in order, with the time from the puzzle's unlock
+fn timeline(leaderboard: &Leaderboard) {
+    for member in &leaderboard.members {
+        println!("{} ({} stars)", member.display_name(), member.stars);
+        for (count, star) in leaderboard::timeline(member).into_iter().enumerate() {
+            println!(
+                "    {:>2}* day {:>2} part {} {:>14} after unlock",
+                count + 1,
+                star.day,
+                star.part,
+                format_duration(
+                    star.timestamp - leaderboard::unlock_time(leaderboard.year, star.day)
+                )
+            );
+        }
+    }
+}
+
+/// part 1 to part 2 per member and day, with the total
+fn deltas(leaderboard: &Leaderboard) {
+    for member in &leaderboard.members {
+        let deltas = leaderboard::deltas(member);
+        if deltas.is_empty() {
+            continue;
+        }
+        let total = deltas.iter().map(|(_, delta)| delta).sum();
+        println!(
+            "{} (total {})",
+            member.display_name(),
+            format_duration(total)
+        );
+        for (day, delta) in deltas {
+            println!("    day {:>2} {:>14}", day, format_duration(delta));
+        }
+    }
+}
+
+/// the member's time from unlock to star against the best and the median of the leaderboard
+fn compare(leaderboard: &Leaderboard, id: u64) -> Result<(), String> {
+    let comparisons =
+        leaderboard::compare(leaderboard, id).ok_or_else(|| format!("No member with id {}", id))?;
+    let name = leaderboard.member(id).unwrap().display_name();
+    let time = |time: Option<i64>| time.map_or_else(|| String::from("-"), format_duration);
+
+    println!(
+        "{:>6} {:>4} {:>14} {:>14} {:>14} {:>7}",
+        "day", "part", name, "best", "median", "rank"
+    );
+    for comparison in comparisons {
+        println!(
+            "{:>6} {:>4} {:>14} {:>14} {:>14} {:>7}",
+            comparison.day,
+            comparison.part,
+            time(comparison.own),
+            time(comparison.best),
+            time(comparison.median),
+            comparison.rank.map_or_else(
+                || String::from("-"),
+                |rank| format!("{}/{}", rank, comparison.finishers)
+            )
+        );
+    }
+    Ok(())
+}
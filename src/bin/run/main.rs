@@ -0,0 +1,380 @@
+mod args;
+mod bench;
+mod cache;
+mod crosscheck;
+mod dashboard;
+mod doctor;
+mod explain;
+mod leaderboard;
+mod perf_history;
+mod plugins;
+mod profile;
+mod repl;
+mod seal;
+mod stress;
+mod verify;
+mod watch;
+mod writeup;
+
+use crate::args::{parse_day, Args};
+use crate::cache::Cache;
+use crate::plugins::{AnySolver, Prepared};
+use crate::profile::Profile;
+use aoc2021::budget::Budget;
+use aoc2021::config::{Config, OUTPUT_FORMATS};
+use aoc2021::hash::fnv1a;
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use aoc2021::{input, json};
+use std::borrow::Cow;
+use std::path::Path;
+use std::process::ExitCode;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// exit code for a timed out solver, same as coreutils `timeout`
+const TIMEOUT_EXIT_CODE: u8 = 124;
+
+const USAGE: &str = "\
+Usage:
+    run [--timeout 10s] [--variant name] [--strict] [--profile name] [--format json] [--no-cache] <day> [<part>]
+    run all [--timeout 10s] [--profile name] [--format json] [--no-cache]
+    run crosscheck <day> [<part>] [--random count] [--size size] [--seed seed] [--profile name]
+    run bench <day> [<part>] [--iterations count] [--profile name]
+    run perf-history <day> <part> [--variant name] [--machine id]
+    run stress <day> [<part>] [--sizes 1k,10k,100k,1M] [--seed seed] [--timeout 10s]
+    run seal [<day>] [--profile name | --all-profiles]
+    run unseal [<day>] [--profile name | --all-profiles]
+    run verify [<day>] [--timeout 10s] [--profile name | --all-profiles]
+    run watch <day> [--interval 500ms] [--timeout 10s] [--profile name | --all-profiles]
+    run leaderboard [rankings|timeline|deltas|compare] <file> [--scoring name] [--member id]
+    run explain <day> <part> [--input name] [--profile name]
+    run writeup [<day>] [--out dir] [--profile name] [--timeout 10s]
+    run repl <day> [--input name] [--profile name]
+    run dashboard [--profile name] [--timeout 10s]
+    run config
+    run doctor
+
+The default profile reads input/, --profile name reads input/profiles/name/.
+Defaults for paths, profile, format and timeouts come from the closest aoc.toml.
+Answers are cached in .answer-cache until the input or the day's source changes.
+Every bench run is added to .bench-history with the commit, date and machine.
+Plugins in plugins/ add variants named plugin/variant, see examples/day1_plugin.rs.";
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// `aoc.toml` from the current directory upward, loaded at the start of `main`
+pub fn config() -> &'static Config {
+    CONFIG.get().expect("config not loaded yet")
+}
+
+fn load_config() -> Result<(), String> {
+    let current_dir = std::env::current_dir().map_err(|err| err.to_string())?;
+    let config = Config::discover(&current_dir, Path::new(env!("CARGO_MANIFEST_DIR")))?;
+    let _ = CONFIG.set(config);
+    Ok(())
+}
+
+/// `--timeout`, or else the day's timeout from `aoc.toml`
+pub fn timeout(args: &Args, day: u8) -> Result<Option<Duration>, String> {
+    Ok(args.timeout()?.or_else(|| config().timeout(day)))
+}
+
+/// `--format`, or else `output` from `aoc.toml`
+fn output_format(args: &Args) -> Result<&str, String> {
+    match args.option("format") {
+        Some(format) if !OUTPUT_FORMATS.contains(&format) => Err(format!(
+            "Invalid format {}, expected one of {:?}",
+            format, OUTPUT_FORMATS
+        )),
+        Some(format) => Ok(format),
+        None => Ok(config().output()),
+    }
+}
+
+pub enum Outcome {
+    Answer(String),
+    Timeout,
+    Panicked,
+    /// a plugin reported an error
+    Failed(String),
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{}", answer),
+            Outcome::Timeout => write!(f, "timeout"),
+            Outcome::Panicked => write!(f, "panicked"),
+            Outcome::Failed(message) => write!(f, "failed: {}", message),
+        }
+    }
+}
+
+/// the plaintext of an input file, opening it if it is sealed
+pub fn read_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            aoc2021::seal::open(&content, &config().key_path())
+                .map(Cow::into_owned)
+                .map_err(|err| err.to_string())
+        })
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Run the solver on a separate thread so that we can stop waiting for it after the timeout,
+/// even if the solver does not check its budget.
+pub fn solve_with_timeout(
+    solver: AnySolver,
+    input: &Prepared,
+    timeout: Option<Duration>,
+) -> Outcome {
+    let input = input.clone();
+    guarded(timeout, move |budget| solver.solve(&input, budget))
+}
+
+/// Parses the input for the built-in solvers before their parts are timed, on a thread like a
+/// solver. A parse that panics or runs out of time does so again in every built-in part.
+pub fn parse_ahead(input: &Prepared, timeout: Option<Duration>) {
+    if !input.is_parsed() {
+        let input = input.clone();
+        guarded(timeout, move |_| {
+            input.parsed();
+            Outcome::Answer(String::new())
+        });
+    }
+}
+
+/// Runs `task` on a separate thread, its outcome or how the thread ended
+pub fn guarded(
+    timeout: Option<Duration>,
+    task: impl FnOnce(&Budget) -> Outcome + Send + 'static,
+) -> Outcome {
+    let budget = timeout.map_or_else(Budget::unlimited, Budget::with_timeout);
+    let cancel = budget.cancel_handle();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    std::thread::spawn(move || {
+        let _ = sender.send(task(&budget));
+    });
+
+    match receiver.recv_timeout(timeout.unwrap_or(Duration::MAX)) {
+        Ok(Outcome::Timeout) | Err(RecvTimeoutError::Timeout) => {
+            cancel.cancel();
+            Outcome::Timeout
+        }
+        Ok(outcome) => outcome,
+        // the solver panicked, its message has already been printed
+        Err(RecvTimeoutError::Disconnected) => Outcome::Panicked,
+    }
+}
+
+/// Reports the formatting anomalies that are otherwise silently normalized away
+fn check_input(path: &Path, input: &str) -> Result<(), String> {
+    let anomalies = input::anomalies(input);
+    if anomalies.is_empty() {
+        return Ok(());
+    }
+    let mut report = format!("{}:", path.display());
+    for anomaly in anomalies {
+        report += &format!("\n    {}", anomaly);
+    }
+    Err(report)
+}
+
+/// Runs the requested day, or every day for `all`
+fn run(args: &Args) -> Result<ExitCode, String> {
+    let day = args.positional(0).ok_or_else(|| USAGE.to_string())?;
+    let days = match day {
+        "all" => registry::DAYS.iter().map(|day| day.day).collect(),
+        _ => vec![parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?],
+    };
+
+    let mut cache = Cache::load();
+    let mut result = Ok(ExitCode::SUCCESS);
+    for day in days {
+        result = run_day(args, day, &mut cache);
+        if !matches!(result, Ok(ExitCode::SUCCESS)) {
+            break;
+        }
+    }
+    cache.save()?;
+    result
+}
+
+/// Parses the input once and runs the requested parts on it, or both parts if none is given.
+/// Cached answers are printed without parsing unless `--no-cache` is given.
+fn run_day(args: &Args, day: u8, cache: &mut Cache) -> Result<ExitCode, String> {
+    let part = args.positional(1);
+
+    let profile = Profile::from_args(args)?;
+    let input_path = profile.input_path(day);
+    let input = profile.read_input(day)?;
+    if args.flag("strict") {
+        check_input(&input_path, &input)?;
+    }
+
+    if (day, part) == (24, Some("code")) {
+        println!(
+            "{}",
+            aoc2021::day24::part1_instructions_to_code(&input::normalize(&input))
+        );
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let variant = args.option("variant").unwrap_or(DEFAULT_VARIANT);
+    let parts = match part {
+        Some(part) => vec![part.parse().unwrap_or(0)],
+        None => vec![1, 2],
+    };
+    let solvers = parts
+        .into_iter()
+        .map(|part| {
+            plugins::solver(day, part, variant).ok_or_else(|| {
+                format!(
+                    "Unknown Day Part combination: Day {} Part {} Variant {}",
+                    day,
+                    args.positional(1).unwrap_or_default(),
+                    variant
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let input_hash = fnv1a(input::normalize(&input).as_bytes());
+    if let Some(previous) = cache.check_input(&input_path, input_hash) {
+        eprintln!(
+            "{} has changed since the last run (hash {:016x}, now {:016x}), computing its answers again",
+            input_path.display(),
+            previous,
+            input_hash
+        );
+    }
+
+    let timeout = timeout(args, day)?;
+    let json = output_format(args)? == "json";
+    let mut prepared: Option<Prepared> = None;
+
+    for solver in solvers {
+        let cached = match solver
+            .builtin()
+            .and_then(|solver| cache.get(solver, input_hash))
+        {
+            Some(answer) if !args.flag("no-cache") => Some(answer.to_string()),
+            _ => None,
+        };
+        let mut start = Instant::now();
+        let outcome = match cached.clone() {
+            Some(answer) => Outcome::Answer(answer),
+            None => {
+                let prepared = match &prepared {
+                    Some(prepared) => prepared,
+                    None => prepared.insert(Prepared::new(day, &input)?),
+                };
+                if solver.builtin().is_some() && !prepared.is_parsed() {
+                    let start = Instant::now();
+                    parse_ahead(prepared, timeout);
+                    eprintln!("Day {} parse: {:?}", day, start.elapsed());
+                }
+                // the part's time leaves out the parse
+                start = Instant::now();
+                solve_with_timeout(solver, prepared, timeout)
+            }
+        };
+
+        if let (None, Some(solver), Outcome::Answer(answer)) = (&cached, solver.builtin(), &outcome)
+        {
+            cache.insert(solver, input_hash, answer);
+        }
+
+        match outcome {
+            Outcome::Answer(answer) if json => println!(
+                "{{\"day\": {}, \"part\": {}, \"answer\": {}, \"seconds\": {}, \"cached\": {}}}",
+                day,
+                solver.part(),
+                json::Value::from(answer.trim_end()),
+                start.elapsed().as_secs_f64(),
+                cached.is_some()
+            ),
+            Outcome::Answer(answer) => {
+                match cached {
+                    Some(_) => eprintln!("Day {} Part {}: cached", day, solver.part()),
+                    None => eprintln!("Day {} Part {}: {:?}", day, solver.part(), start.elapsed()),
+                }
+                println!("{}", answer);
+            }
+            Outcome::Timeout => {
+                println!("timeout after {:?}", timeout.unwrap_or_default());
+                return Ok(ExitCode::from(TIMEOUT_EXIT_CODE));
+            }
+            Outcome::Panicked => return Ok(ExitCode::from(101)),
+            Outcome::Failed(message) => {
+                println!("{}: {}", solver.variant(), message);
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Shows where the config was found and the settings in effect
+fn show_config() -> Result<ExitCode, String> {
+    let config = config();
+    match &config.path {
+        Some(path) => println!("config:          {}", path.display()),
+        None => println!("config:          none found, using the defaults"),
+    }
+    println!("year:            {}", config.year());
+    println!("input_dir:       {}", config.input_dir().display());
+    println!("src_dir:         {}", config.src_dir().display());
+    println!("template_dir:    {}", config.template_dir().display());
+    println!("plugin_dir:      {}", config.plugin_dir().display());
+    println!(
+        "default_profile: {}",
+        config.default_profile().unwrap_or("default")
+    );
+    match config.session_token() {
+        Some(path) => println!("session_token:   {}", path.display()),
+        None => println!("session_token:   not set"),
+    }
+    println!("output:          {}", config.output());
+    match config.default_timeout() {
+        Some(timeout) => println!("timeout:         {:?}", timeout),
+        None => println!("timeout:         none"),
+    }
+    for day in registry::DAYS.iter().map(|day| day.day) {
+        if config.timeout(day) != config.default_timeout() {
+            println!("timeout day{:<5} {:?}", day, config.timeout(day).unwrap());
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+pub fn main() -> ExitCode {
+    let result = load_config().and_then(|_| Args::parse(std::env::args().skip(1)));
+    let result = result.and_then(|args| match args.positional(0) {
+        Some("config") => show_config(),
+        Some("crosscheck") => crosscheck::main(&args),
+        Some("dashboard") => dashboard::main(&args),
+        Some("doctor") => doctor::main(&args),
+        Some("explain") => explain::main(&args),
+        Some("leaderboard") => leaderboard::main(&args),
+        Some("bench") => bench::main(&args),
+        Some("perf-history") => perf_history::main(&args),
+        Some("repl") => repl::main(&args),
+        Some("seal") => seal::main(&args, true),
+        Some("stress") => stress::main(&args),
+        Some("unseal") => seal::main(&args, false),
+        Some("verify") => verify::main(&args),
+        Some("watch") => watch::main(&args),
+        Some("writeup") => writeup::main(&args),
+        _ => run(&args),
+    });
+
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,167 @@
+use crate::args::{parse_day, Args};
+use crate::config;
+use aoc2021::perf::{self, Record};
+use aoc2021::registry::DEFAULT_VARIANT;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// changes of more than this many percent between commits are pointed out
+const SIGNIFICANT_CHANGE: f64 = 10.0;
+
+fn history_path() -> PathBuf {
+    config().root.join(".bench-history")
+}
+
+/// the output of a git command in the project, `None` outside of a git checkout
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .arg("-C")
+        .arg(&config().root)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `git rev-parse HEAD` of the project with [`perf::DIRTY`] after it if there are uncommitted
+/// changes, `unknown` outside of a git checkout
+fn commit() -> String {
+    match git(&["rev-parse", "HEAD"]) {
+        Some(commit)
+            if git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()) =>
+        {
+            commit + perf::DIRTY
+        }
+        Some(commit) => commit,
+        None => String::from("unknown"),
+    }
+}
+
+/// the systemd machine id, or else the host name, so that timings of different machines are not
+/// compared
+fn machine() -> String {
+    [
+        "/etc/machine-id",
+        "/var/lib/dbus/machine-id",
+        "/etc/hostname",
+    ]
+    .iter()
+    .filter_map(|path| std::fs::read_to_string(path).ok())
+    .map(|id| id.trim().to_string())
+    .chain(std::env::var("HOSTNAME"))
+    .find(|id| !id.is_empty() && !id.contains(char::is_whitespace))
+    .unwrap_or_else(|| String::from("unknown"))
+}
+
+/// Appends the medians of a bench run to the history
+pub fn append(medians: &[(u8, u8, &str, Duration)]) -> Result<(), String> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() as i64);
+    let (commit, machine) = (commit(), machine());
+    let records = medians
+        .iter()
+        .map(|&(day, part, variant, median)| {
+            let record = Record {
+                time,
+                commit: commit.clone(),
+                machine: machine.clone(),
+                day,
+                part,
+                variant: variant.to_string(),
+                median_nanos: median.as_nanos() as u64,
+            };
+            format!("{}\n", record)
+        })
+        .collect::<String>();
+
+    let path = history_path();
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(records.as_bytes()))
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Shows the runtime of a part over the benched commits of this machine, or of `--machine id`
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let usage = "Usage: run perf-history <day> <part> [--variant name] [--machine id]";
+    let day = args.positional(1).ok_or(usage)?;
+    let day = parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?;
+    let part: u8 = match args.positional(2).map(str::parse) {
+        Some(Ok(part)) => part,
+        _ => return Err(usage.to_string()),
+    };
+    let variant = args.option("variant").unwrap_or(DEFAULT_VARIANT);
+    let machine = args.option("machine").map_or_else(machine, str::to_string);
+
+    let content = std::fs::read_to_string(history_path()).unwrap_or_default();
+    let records = content
+        .lines()
+        .filter_map(Record::parse)
+        .filter(|record| {
+            (
+                record.day,
+                record.part,
+                record.variant.as_str(),
+                &record.machine,
+            ) == (day, part, variant, &machine)
+        })
+        .collect::<Vec<_>>();
+    let dirty = records.iter().filter(|record| record.is_dirty()).count();
+    let timings = perf::by_commit(&records);
+    if timings.is_empty() {
+        return Err(format!(
+            "No bench history for Day {} Part {} {} on machine {}, run bench first",
+            day, part, variant, machine
+        ));
+    }
+
+    println!(
+        "Day {} Part {} {} on machine {}, {} commits",
+        day,
+        part,
+        variant,
+        machine,
+        timings.len()
+    );
+    let medians = timings
+        .iter()
+        .map(|timing| timing.median_nanos)
+        .collect::<Vec<_>>();
+    println!("{}\n", perf::sparkline(&medians));
+    if dirty > 0 {
+        println!(
+            "Left out {} runs with uncommitted changes, they belong to no commit\n",
+            dirty
+        );
+    }
+
+    println!(
+        "{:<10}  {:<10}  {:>4}  {:>12}  change",
+        "commit", "date", "runs", "median"
+    );
+    for (timing, change) in timings.iter().zip(perf::changes(&timings)) {
+        let change = match change {
+            Some(change) if change > SIGNIFICANT_CHANGE => format!("{:+.1}% regression", change),
+            Some(change) if change < -SIGNIFICANT_CHANGE => {
+                format!("{:+.1}% improvement", change)
+            }
+            Some(change) => format!("{:+.1}%", change),
+            None => String::new(),
+        };
+        println!(
+            "{:<10.10}  {:<10}  {:>4}  {:>12?}  {}",
+            timing.commit,
+            perf::format_date(timing.time),
+            timing.runs,
+            Duration::from_nanos(timing.median_nanos),
+            change
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
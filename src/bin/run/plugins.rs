@@ -0,0 +1,144 @@
+//! The built-in solvers and those of the plugins in `plugin_dir`, side by side
+//!
+//! Plugin variants are named `plugin/variant`, e.g. `run --variant example/window 1 2`.
+
+use crate::{config, Outcome};
+use aoc2021::budget::{Budget, Exhausted};
+use aoc2021::input::normalize;
+use aoc2021::plugin::{self, Plugin};
+use aoc2021::registry::{self, Parsed, Solver};
+use std::sync::{Arc, OnceLock};
+
+static PLUGINS: OnceLock<Vec<Plugin>> = OnceLock::new();
+
+/// The plugins in `plugin_dir`, loaded on first use, plugins that fail to load are skipped
+pub fn plugins() -> &'static [Plugin] {
+    PLUGINS.get_or_init(|| {
+        plugin::load_dir(&config().plugin_dir())
+            .into_iter()
+            .filter_map(|plugin| {
+                plugin
+                    .map_err(|err| eprintln!("Skipping plugin {}", err))
+                    .ok()
+            })
+            .collect()
+    })
+}
+
+/// A solver of the registry or of a plugin
+#[derive(Clone, Copy)]
+pub enum AnySolver {
+    Builtin(&'static Solver),
+    Plugin(&'static plugin::Solver),
+}
+
+/// A day's input for both kinds of solvers, plugins get the normalized text and built-in solvers
+/// the input parsed once, on first use so that runs of plugins alone never call the built-in
+/// parser
+#[derive(Clone)]
+pub struct Prepared {
+    day: u8,
+    pub text: Arc<str>,
+    parsed: Arc<OnceLock<Box<Parsed>>>,
+}
+
+impl Prepared {
+    pub fn new(day: u8, text: &str) -> Result<Self, String> {
+        if !registry::DAYS.iter().any(|entry| entry.day == day) {
+            return Err(format!("Unknown day: {}", day));
+        }
+        Ok(Prepared {
+            day,
+            text: normalize(text).into(),
+            parsed: Arc::default(),
+        })
+    }
+
+    /// with the input parsed already, for callers that time the parser themselves
+    pub fn with_parsed(day: u8, text: &str, parsed: Box<Parsed>) -> Self {
+        Prepared {
+            day,
+            text: normalize(text).into(),
+            parsed: Arc::new(OnceLock::from(parsed)),
+        }
+    }
+
+    pub fn is_parsed(&self) -> bool {
+        self.parsed.get().is_some()
+    }
+
+    /// the input for the built-in solvers, parsed by the first caller
+    pub fn parsed(&self) -> &Parsed {
+        &**self.parsed.get_or_init(|| {
+            registry::parse(self.day, &self.text).expect("days are checked by Prepared::new")
+        })
+    }
+}
+
+impl AnySolver {
+    pub fn part(self) -> u8 {
+        match self {
+            AnySolver::Builtin(solver) => solver.part,
+            AnySolver::Plugin(solver) => solver.part,
+        }
+    }
+
+    pub fn variant(self) -> &'static str {
+        match self {
+            AnySolver::Builtin(solver) => solver.variant,
+            AnySolver::Plugin(solver) => &solver.variant,
+        }
+    }
+
+    /// the registry's solver, answers of plugins are not cached
+    pub fn builtin(self) -> Option<&'static Solver> {
+        match self {
+            AnySolver::Builtin(solver) => Some(solver),
+            AnySolver::Plugin(_) => None,
+        }
+    }
+
+    /// Solves on the current thread, plugins do not check the budget
+    pub fn solve(self, input: &Prepared, budget: &Budget) -> Outcome {
+        match self {
+            AnySolver::Builtin(solver) => match (solver.solve)(input.parsed(), budget) {
+                Ok(answer) => Outcome::Answer(answer),
+                Err(Exhausted) => Outcome::Timeout,
+            },
+            AnySolver::Plugin(solver) => match solver.solve(&input.text) {
+                Ok(answer) => Outcome::Answer(answer),
+                Err(message) => Outcome::Failed(message),
+            },
+        }
+    }
+}
+
+/// The built-in variants of the part followed by those of the plugins
+pub fn variants(day: u8, part: u8) -> Vec<AnySolver> {
+    let plugin_solvers = plugins()
+        .iter()
+        .flat_map(|plugin| &plugin.solvers)
+        .filter(move |solver| (solver.day, solver.part) == (day, part));
+    registry::variants(day, part)
+        .map(AnySolver::Builtin)
+        .chain(plugin_solvers.map(AnySolver::Plugin))
+        .collect()
+}
+
+pub fn solver(day: u8, part: u8, variant: &str) -> Option<AnySolver> {
+    // plugins are only loaded when a plugin variant is asked for
+    match registry::solver(day, part, variant) {
+        Some(solver) => Some(AnySolver::Builtin(solver)),
+        None => variants(day, part)
+            .into_iter()
+            .find(|solver| solver.variant() == variant),
+    }
+}
+
+/// the plugin variants of the part, for commands that run one variant per part
+pub fn plugin_variants(day: u8, part: u8) -> Vec<AnySolver> {
+    variants(day, part)
+        .into_iter()
+        .filter(|solver| solver.builtin().is_none())
+        .collect()
+}
@@ -0,0 +1,192 @@
+use crate::args::Args;
+use crate::{config, read_file};
+use aoc2021::expected::{self, Expected};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// named profiles live in `input/profiles/<name>/`
+const PROFILES_DIR: &str = "profiles";
+
+/// the expected answers of all days of a named profile, see [`expected::parse_sections`]
+pub const ANSWERS_FILE: &str = "answers.expected";
+
+/// Whose puzzle inputs to use
+///
+/// The default profile is `input/` with the examples and `.expected` sidecars, a named profile
+/// has its own `input/profiles/<name>/dayN.txt` inputs and one `answers.expected` file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    name: Option<String>,
+}
+
+/// An input file with its name, `full` for `dayN.txt` and e.g. `example1` for
+/// `dayN.example1.txt`, and its expected answers if known
+pub struct DayInput {
+    pub name: String,
+    pub path: PathBuf,
+    pub expected: Option<Expected>,
+}
+
+impl Profile {
+    /// `default` names the default profile
+    pub fn named(name: &str) -> Result<Self, String> {
+        if name == "default" {
+            return Ok(Profile::default());
+        }
+        let profile = Profile {
+            name: Some(name.to_string()),
+        };
+        if profile.dir().is_dir() {
+            Ok(profile)
+        } else {
+            Err(format!(
+                "Unknown profile {}, there is no {}",
+                name,
+                profile.dir().display()
+            ))
+        }
+    }
+
+    /// the default and all named profiles
+    pub fn all() -> Vec<Self> {
+        let mut names = std::fs::read_dir(config().input_dir().join(PROFILES_DIR))
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                entry.file_type().ok()?.is_dir().then_some(())?;
+                entry.file_name().into_string().ok()
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+
+        std::iter::once(Profile::default())
+            .chain(names.into_iter().map(|name| Profile { name: Some(name) }))
+            .collect()
+    }
+
+    /// `--profile name`, or else `default_profile` from `aoc.toml`
+    pub fn from_args(args: &Args) -> Result<Self, String> {
+        args.option("profile")
+            .or(config().default_profile())
+            .map_or_else(|| Ok(Profile::default()), Profile::named)
+    }
+
+    /// all profiles with `--all-profiles`, otherwise the one from [`Profile::from_args`]
+    pub fn selected(args: &Args) -> Result<Vec<Self>, String> {
+        if args.flag("all-profiles") {
+            Ok(Profile::all())
+        } else {
+            Profile::from_args(args).map(|profile| vec![profile])
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.name.as_deref().unwrap_or("default")
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        match &self.name {
+            Some(name) => config().input_dir().join(PROFILES_DIR).join(name),
+            None => config().input_dir(),
+        }
+    }
+
+    pub fn input_path(&self, day: u8) -> PathBuf {
+        self.dir().join(format!("day{}.txt", day))
+    }
+
+    pub fn read_input(&self, day: u8) -> Result<String, String> {
+        read_file(&self.input_path(day))
+    }
+
+    /// the input files of the day, sorted by name, with their expected answers
+    pub fn inputs(&self, day: u8) -> Result<Vec<DayInput>, String> {
+        match &self.name {
+            None => day_input_paths(day)
+                .into_iter()
+                .map(|(name, path)| {
+                    let expected = read_expected(&path.with_extension("expected"))?;
+                    Ok(DayInput {
+                        name,
+                        path,
+                        expected,
+                    })
+                })
+                .collect(),
+            Some(_) => {
+                let path = self.input_path(day);
+                if !path.exists() {
+                    return Ok(vec![]);
+                }
+                Ok(vec![DayInput {
+                    name: String::from("full"),
+                    path,
+                    expected: read_answers(&self.answers_path())?.remove(&day),
+                }])
+            }
+        }
+    }
+
+    /// the input of the day called `name`, e.g. `full` or `example`
+    pub fn input(&self, day: u8, name: &str) -> Result<DayInput, String> {
+        self.inputs(day)?
+            .into_iter()
+            .find(|input| input.name == name)
+            .ok_or_else(|| format!("Day {} has no input named {}", day, name))
+    }
+
+    /// the files with expected answers, the sidecars or the answers file
+    pub fn expected_paths(&self, day: u8) -> Vec<PathBuf> {
+        match &self.name {
+            None => day_input_paths(day)
+                .into_iter()
+                .map(|(_, path)| path.with_extension("expected"))
+                .collect(),
+            Some(_) => vec![self.answers_path()],
+        }
+    }
+
+    pub fn answers_path(&self) -> PathBuf {
+        self.dir().join(ANSWERS_FILE)
+    }
+}
+
+/// the default profile's input files of the day with their names, sorted by name
+fn day_input_paths(day: u8) -> Vec<(String, PathBuf)> {
+    let prefix = format!("day{}", day);
+    let mut inputs = std::fs::read_dir(config().input_dir())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let stem = path.file_name()?.to_str()?.strip_suffix(".txt")?;
+            let name = match stem.strip_prefix(&prefix)? {
+                "" => String::from("full"),
+                name => name.strip_prefix('.')?.to_string(),
+            };
+            Some((name, path))
+        })
+        .collect::<Vec<_>>();
+    inputs.sort();
+    inputs
+}
+
+fn read_expected(path: &Path) -> Result<Option<Expected>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => expected::parse(&content)
+            .map(Some)
+            .map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// no answers file means no answers are known yet
+fn read_answers(path: &Path) -> Result<BTreeMap<u8, Expected>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => {
+            expected::parse_sections(&content).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        Err(_) => Ok(BTreeMap::new()),
+    }
+}
@@ -0,0 +1,56 @@
+use crate::args::{parse_day, Args};
+use crate::profile::Profile;
+use crate::read_file;
+use aoc2021::registry;
+use std::io::{BufRead, Write};
+use std::process::ExitCode;
+
+/// Reads commands for a day's explorer from stdin until `quit`, `exit` or the end of input
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let usage = "Usage: run repl <day> [--input name] [--profile name]";
+    let day = args.positional(1).ok_or(usage)?;
+    let day = parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?;
+    let explorer = registry::explorer(day).ok_or_else(|| {
+        let days = registry::EXPLORERS
+            .iter()
+            .map(|explorer| explorer.day.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "Day {} has no repl, days with one: {}",
+            day,
+            days.join(", ")
+        )
+    })?;
+
+    let profile = Profile::from_args(args)?;
+    let input = profile.input(day, args.option("input").unwrap_or("full"))?;
+    let mut session = (explorer.start)(&read_file(&input.path)?);
+    println!(
+        "Day {} on {}, type help for the commands and quit to leave",
+        day,
+        input.path.display()
+    );
+
+    let mut stdout = std::io::stdout();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("day{}> ", day);
+        stdout.flush().map_err(|err| err.to_string())?;
+        let line = match lines.next() {
+            Some(line) => line.map_err(|err| err.to_string())?,
+            None => {
+                println!();
+                break;
+            }
+        };
+        match line.trim() {
+            "quit" | "exit" => break,
+            line => match session.execute(line) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{}", output),
+                Err(err) => println!("error: {}", err),
+            },
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,85 @@
+use crate::args::{parse_day, Args};
+use crate::config;
+use crate::profile::Profile;
+use aoc2021::registry;
+use aoc2021::seal::{self, Key, SealError};
+use std::io::Read;
+use std::path::Path;
+use std::process::ExitCode;
+
+/// the OS's cryptographically secure random number generator
+const RANDOM_SOURCE: &str = "/dev/urandom";
+
+/// Keys and nonces, a repeated nonce would give away the inputs sealed with it
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0; N];
+    std::fs::File::open(RANDOM_SOURCE)
+        .and_then(|mut source| source.read_exact(&mut bytes))
+        .map_err(|err| format!("{}: {}, no secure random numbers", RANDOM_SOURCE, err))?;
+    Ok(bytes)
+}
+
+/// The local key, a new one is created in the key file for sealing if there is none yet
+fn key(create: bool) -> Result<Key, String> {
+    let key_path = config().key_path();
+    match seal::local_key(&key_path) {
+        Err(SealError::MissingKey) if create => {
+            let key = Key::new(random_bytes()?);
+            write_key(&key, &key_path).map_err(|err| format!("{}: {}", key_path.display(), err))?;
+            println!(
+                "Created a new key in {}, keep a copy: sealed inputs can't be opened without it",
+                key_path.display()
+            );
+            Ok(key)
+        }
+        result => result.map_err(|err| err.to_string()),
+    }
+}
+
+fn write_key(key: &Key, path: &Path) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    std::io::Write::write_all(&mut options.open(path)?, key.to_hex().as_bytes())
+}
+
+/// Seals or unseals the full inputs, the examples are public anyway
+pub fn main(args: &Args, sealing: bool) -> Result<ExitCode, String> {
+    let days = match args.positional(1) {
+        Some(day) => vec![parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?],
+        None => registry::DAYS.iter().map(|day| day.day).collect(),
+    };
+    let profiles = Profile::selected(args)?;
+    let key = key(sealing)?;
+
+    for profile in &profiles {
+        for &day in &days {
+            seal_file(&profile.input_path(day), &key, sealing)?;
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn seal_file(path: &Path, key: &Key, sealing: bool) -> Result<(), String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return Ok(()),
+    };
+    let changed = match (sealing, seal::is_sealed(&content)) {
+        (true, false) => seal::seal(key, random_bytes()?, &content),
+        (false, true) => {
+            seal::unseal(key, &content).map_err(|err| format!("{}: {}", path.display(), err))?
+        }
+        _ => return Ok(()),
+    };
+    std::fs::write(path, changed).map_err(|err| format!("{}: {}", path.display(), err))?;
+    println!(
+        "{} {}",
+        if sealing { "Sealed" } else { "Unsealed" },
+        path.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use crate::args::{parse_day, Args};
+use crate::plugins::{AnySolver, Prepared};
+use crate::{solve_with_timeout, Outcome};
+use aoc2021::budget::Budget;
+use aoc2021::registry::{self, Solver};
+use aoc2021::rng::Rng;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+const DEFAULT_SIZES: &str = "100,1k,10k";
+
+/// without a timeout from `--timeout` or `aoc.toml`, so large sizes do not run forever
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// runs faster than this are timed a few more times and the fastest one counts
+const REPEAT_BELOW: Duration = Duration::from_millis(100);
+const REPEATS: usize = 5;
+
+/// `1k` is 1000 and `1M` a million
+fn parse_size(text: &str) -> Option<usize> {
+    let (number, factor) = match text.char_indices().last()? {
+        (idx, 'k' | 'K') => (&text[..idx], 1_000),
+        (idx, 'm' | 'M') => (&text[..idx], 1_000_000),
+        _ => (text, 1),
+    };
+    number.parse::<usize>().ok()?.checked_mul(factor)
+}
+
+/// The slope of the least squares line through `(ln size, ln seconds)`, so a runtime that grows
+/// like `size^k` gives about `k`
+fn growth_exponent(times: &[(usize, Duration)]) -> Option<f64> {
+    if times.len() < 2 {
+        return None;
+    }
+    let points = times
+        .iter()
+        .map(|&(size, time)| ((size as f64).ln(), time.as_secs_f64().max(1e-9).ln()))
+        .collect::<Vec<_>>();
+    let count = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let covariance = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let variance = points
+        .iter()
+        .map(|(x, _)| (x - mean_x).powi(2))
+        .sum::<f64>();
+    (variance > 0.0).then(|| covariance / variance)
+}
+
+fn describe(exponent: Option<f64>) -> String {
+    match exponent {
+        Some(exponent) => {
+            let name = match exponent.round() as i64 {
+                i64::MIN..=0 => "constant",
+                1 => "linear",
+                2 => "quadratic",
+                3 => "cubic",
+                _ => "worse than cubic",
+            };
+            format!("~n^{:.2} ({})", exponent, name)
+        }
+        None => String::from("too few sizes to fit"),
+    }
+}
+
+/// the solver's runtime, the fastest of a few runs for quick ones
+fn time_solver(
+    solver: &'static Solver,
+    prepared: &Prepared,
+    timeout: Duration,
+) -> Result<Duration, Outcome> {
+    let start = Instant::now();
+    match solve_with_timeout(AnySolver::Builtin(solver), prepared, Some(timeout)) {
+        Outcome::Answer(_) => {}
+        outcome => return Err(outcome),
+    }
+    let mut fastest = start.elapsed();
+    if fastest < REPEAT_BELOW {
+        for _ in 0..REPEATS {
+            let start = Instant::now();
+            let _ = (solver.solve)(prepared.parsed(), &Budget::unlimited());
+            fastest = fastest.min(start.elapsed());
+        }
+    }
+    Ok(fastest)
+}
+
+/// A parse or solver row of the report: a time per size, a timeout or panic stops it
+struct Row {
+    name: String,
+    solver: Option<&'static Solver>,
+    times: Vec<(usize, Duration)>,
+    stopped: Option<String>,
+}
+
+/// Times parsing and every variant of the day's parts on generated inputs of increasing size
+/// and fits how the runtime grows
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let day = args
+        .positional(1)
+        .and_then(parse_day)
+        .ok_or_else(|| crate::USAGE.to_string())?;
+    let parts = match args.positional(2) {
+        Some(part) => vec![part
+            .parse()
+            .map_err(|_| format!("Invalid part: {}", part))?],
+        None => vec![1, 2],
+    };
+    let generator = registry::generator(day).ok_or_else(|| {
+        let days = registry::GENERATORS
+            .iter()
+            .map(|generator| generator.day.to_string())
+            .collect::<Vec<_>>();
+        format!(
+            "No input generator for day {}, days with one: {}",
+            day,
+            days.join(", ")
+        )
+    })?;
+    let mut sizes = args
+        .option("sizes")
+        .unwrap_or(DEFAULT_SIZES)
+        .split(',')
+        .map(|size| parse_size(size.trim()).ok_or_else(|| format!("Invalid size: {}", size)))
+        .collect::<Result<Vec<_>, _>>()?;
+    sizes.sort_unstable();
+    sizes.dedup();
+    let seed = args.parsed_option("seed")?.unwrap_or(0);
+    let timeout = crate::timeout(args, day)?.unwrap_or(DEFAULT_TIMEOUT);
+
+    let mut rows = vec![Row {
+        name: String::from("parse"),
+        solver: None,
+        times: vec![],
+        stopped: None,
+    }];
+    for &part in &parts {
+        rows.extend(registry::variants(day, part).map(|solver| Row {
+            name: format!("part{} {}", part, solver.variant),
+            solver: Some(solver),
+            times: vec![],
+            stopped: None,
+        }));
+    }
+
+    for &size in &sizes {
+        eprintln!("Day {} size {}: generating", day, size);
+        let input = (generator.generate)(&mut Rng::new(seed), size);
+        let start = Instant::now();
+        let parsed = registry::parse(day, &input).ok_or_else(|| format!("Unknown day: {}", day))?;
+        rows[0].times.push((size, start.elapsed()));
+        let prepared = Prepared::with_parsed(day, &input, parsed);
+
+        for row in rows.iter_mut().skip(1).filter(|row| row.stopped.is_none()) {
+            eprintln!("Day {} size {}: {}", day, size, row.name);
+            match time_solver(row.solver.unwrap(), &prepared, timeout) {
+                Ok(time) => row.times.push((size, time)),
+                Err(outcome) => row.stopped = Some(format!("{} at {}", outcome, size)),
+            }
+        }
+    }
+
+    print!("Day {:<14}", day);
+    for size in &sizes {
+        print!(" {:>12}", size);
+    }
+    println!();
+    for row in &rows {
+        print!("{:<18}", row.name);
+        for idx in 0..sizes.len() {
+            match row.times.get(idx) {
+                Some((_, time)) => print!(" {:>12}", format!("{:.2?}", time)),
+                None => print!(" {:>12}", "-"),
+            }
+        }
+        print!(" {}", describe(growth_exponent(&row.times)));
+        if let Some(stopped) = &row.stopped {
+            print!(", {}", stopped);
+        }
+        println!();
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,94 @@
+use crate::args::{parse_day, Args};
+use crate::plugins::{self, AnySolver, Prepared};
+use crate::profile::{DayInput, Profile};
+use crate::{parse_ahead, read_file, solve_with_timeout, Outcome};
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Runs every input of the day with known answers and compares them, for every selected profile,
+/// with the default variant and the variants of plugins
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let days = match args.positional(1) {
+        Some(day) => vec![parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?],
+        None => registry::DAYS.iter().map(|day| day.day).collect(),
+    };
+    let profiles = Profile::selected(args)?;
+    let mut failures = 0;
+
+    for profile in &profiles {
+        for &day in &days {
+            failures += verify_day(profile, day, crate::timeout(args, day)?)?;
+        }
+    }
+
+    if failures == 0 {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("{} failures", failures);
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// the number of wrong answers
+fn verify_day(profile: &Profile, day: u8, timeout: Option<Duration>) -> Result<usize, String> {
+    let mut failures = 0;
+    // only name the profile when it is not the default one
+    let prefix = if *profile == Profile::default() {
+        format!("Day {}", day)
+    } else {
+        format!("{} Day {}", profile.name(), day)
+    };
+
+    for DayInput {
+        name,
+        path,
+        expected,
+    } in profile.inputs(day)?
+    {
+        let expected = match expected {
+            Some(expected) => expected,
+            None => continue,
+        };
+        if let Some(reason) = &expected.ignore {
+            println!("{} {}: ignored, {}", prefix, name, reason);
+            continue;
+        }
+
+        let input = read_file(&path)?;
+        let prepared = Prepared::new(day, &input)?;
+        parse_ahead(&prepared, timeout);
+
+        for (&part, answer) in &expected.answers {
+            let solver = registry::solver(day, part, DEFAULT_VARIANT)
+                .ok_or_else(|| format!("No solver for Day {} Part {}", day, part))?;
+            // plugin variants are checked against the same answers
+            let solvers = std::iter::once(AnySolver::Builtin(solver))
+                .chain(plugins::plugin_variants(day, part));
+
+            for solver in solvers {
+                let label = match solver.builtin() {
+                    Some(_) => format!("Part {}", part),
+                    None => format!("Part {} {}", part, solver.variant()),
+                };
+                let start = Instant::now();
+                let outcome = solve_with_timeout(solver, &prepared, timeout);
+                let elapsed = start.elapsed();
+
+                match outcome {
+                    Outcome::Answer(actual) if actual.trim_end() == answer.trim_end() => {
+                        println!("{} {} {}: pass ({:?})", prefix, name, label, elapsed);
+                    }
+                    outcome => {
+                        failures += 1;
+                        println!("{} {} {}: FAIL", prefix, name, label);
+                        println!("    expected: {}", answer.trim_end());
+                        println!("    actual:   {}", outcome.to_string().trim_end());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(failures)
+}
@@ -0,0 +1,106 @@
+use crate::args::{parse_day, Args};
+use crate::config;
+use crate::profile::Profile;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
+use std::time::{Duration, SystemTime};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// the files under `dir`, sorted
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let mut paths = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            source_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// every source file, as the days share modules like the grid and the parser, and the day's
+/// input and answer files, with their modification times, missing files are skipped
+fn snapshot(day: u8, profiles: &[Profile]) -> Vec<(PathBuf, SystemTime)> {
+    let mut sources = vec![];
+    source_files(&config().src_dir(), &mut sources);
+    let inputs = profiles.iter().flat_map(|profile| {
+        let inputs = profile.inputs(day).unwrap_or_default();
+        inputs
+            .into_iter()
+            .map(|input| input.path)
+            .chain(profile.expected_paths(day))
+    });
+
+    sources
+        .into_iter()
+        .chain(inputs)
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}
+
+/// Rebuilds and re-verifies the day whenever a source, its inputs or expected answers change
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let day = args
+        .positional(1)
+        .and_then(parse_day)
+        .ok_or_else(|| crate::USAGE.to_string())?;
+    let interval = match args.option("interval") {
+        Some(value) => aoc2021::config::parse_duration(value)
+            .ok_or_else(|| format!("Invalid interval: {}", value))?,
+        None => DEFAULT_INTERVAL,
+    };
+
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let mut command = Command::new(cargo);
+    command.args([
+        "run",
+        "--quiet",
+        "--manifest-path",
+        concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+    ]);
+    // rebuild with the profile this runner was built with
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    command.args(["--bin", "run", "--", "verify", &day.to_string()]);
+    if let Some(timeout) = args.option("timeout") {
+        command.args(["--timeout", timeout]);
+    }
+    if let Some(profile) = args.option("profile") {
+        command.args(["--profile", profile]);
+    }
+    if args.flag("all-profiles") {
+        command.arg("--all-profiles");
+    }
+    let profiles = Profile::selected(args)?;
+
+    let mut last = None;
+    loop {
+        let current = snapshot(day, &profiles);
+        if last.as_ref() != Some(&current) {
+            print!("{}", CLEAR_SCREEN);
+            println!(
+                "Watching {} and the day {} inputs, Ctrl-C to stop\n",
+                config().src_dir().display(),
+                day
+            );
+            // compile errors and failures are part of the output, only a missing cargo is fatal
+            command
+                .status()
+                .map_err(|err| format!("Failed to run cargo: {}", err))?;
+            last = Some(current);
+        }
+        std::thread::sleep(interval);
+    }
+}
@@ -0,0 +1,231 @@
+//! `run writeup`, a Markdown page per day and an index page
+//!
+//! A page holds the day's `//!` docs, its answers for the full input checked against the
+//! expected ones, their timings next to the bench baseline, the `///` docs of its items and a
+//! visualization for days that have one.
+
+use crate::args::{parse_day, Args};
+use crate::bench::{load_baseline, Baseline, BASELINE_PATH};
+use crate::plugins::{AnySolver, Prepared};
+use crate::profile::Profile;
+use crate::{config, guarded, parse_ahead, solve_with_timeout, Outcome};
+use aoc2021::registry::{self, DEFAULT_VARIANT};
+use aoc2021::writeup;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// relative to the project root
+const DEFAULT_OUT_DIR: &str = "target/writeup";
+
+struct PartReport {
+    part: u8,
+    outcome: Outcome,
+    expected: Option<String>,
+    elapsed: Duration,
+    bench: Option<Duration>,
+}
+
+impl PartReport {
+    fn status(&self) -> &'static str {
+        match (&self.outcome, &self.expected) {
+            (Outcome::Answer(answer), Some(expected))
+                if answer.trim_end() == expected.trim_end() =>
+            {
+                "verified"
+            }
+            (Outcome::Answer(_), Some(_)) => "wrong",
+            (Outcome::Answer(_), None) => "unverified",
+            (_, _) => "failed",
+        }
+    }
+}
+
+/// One row of the index
+struct Summary {
+    day: u8,
+    parts: Vec<PartReport>,
+    documented: usize,
+}
+
+fn run_parts(
+    day: u8,
+    profile: &Profile,
+    prepared: &Prepared,
+    timeout: Option<Duration>,
+    baseline: &Baseline,
+) -> Result<Vec<PartReport>, String> {
+    let expected = profile.input(day, "full")?.expected.unwrap_or_default();
+    parse_ahead(prepared, timeout);
+
+    let mut reports = vec![];
+    for part in 1..=2 {
+        let solver = match registry::solver(day, part, DEFAULT_VARIANT) {
+            Some(solver) => solver,
+            None => continue,
+        };
+        let start = Instant::now();
+        let outcome = solve_with_timeout(AnySolver::Builtin(solver), prepared, timeout);
+        reports.push(PartReport {
+            part,
+            outcome,
+            expected: expected.answers.get(&part).cloned(),
+            elapsed: start.elapsed(),
+            bench: baseline
+                .get(&(day, part, DEFAULT_VARIANT.to_string()))
+                .copied(),
+        });
+    }
+    Ok(reports)
+}
+
+fn day_page(day: u8, source: &str, parts: &[PartReport], visualization: Option<String>) -> String {
+    let mut page = format!(
+        "# Day {}\n\n[Puzzle](https://adventofcode.com/{}/day/{}), solved in `src/day{}.rs`.\n\n",
+        day,
+        config().year(),
+        day,
+        day
+    );
+    let module_docs = writeup::module_docs(source);
+    if !module_docs.is_empty() {
+        page += &format!("{}\n\n", module_docs);
+    }
+
+    page +=
+        "## Answers\n\n| Part | Answer | Status | Time | Bench median |\n|---|---|---|---|---|\n";
+    let mut multi_line = vec![];
+    for report in parts {
+        let answer = report.outcome.to_string();
+        let answer = answer.trim_end();
+        let cell = if answer.contains('\n') {
+            multi_line.push((report.part, answer.to_string()));
+            String::from("see below")
+        } else {
+            format!("`{}`", answer)
+        };
+        let bench = report
+            .bench
+            .map_or_else(|| String::from("-"), |bench| format!("{:.2?}", bench));
+        page += &format!(
+            "| {} | {} | {} | {:.2?} | {} |\n",
+            report.part,
+            cell,
+            report.status(),
+            report.elapsed,
+            bench
+        );
+    }
+    for (part, answer) in multi_line {
+        page += &format!("\nPart {}:\n\n```text\n{}\n```\n", part, answer);
+    }
+
+    page += "\n## Approach\n\n";
+    let items = writeup::item_docs(source);
+    if items.is_empty() {
+        page += "No documented items yet.\n";
+    }
+    for item in items {
+        page += &format!("### `{}`\n\n{}\n\n", item.item, item.doc);
+    }
+
+    if let Some(visualization) = visualization {
+        page += &format!(
+            "\n## Visualization\n\n```text\n{}\n```\n",
+            visualization.trim_end()
+        );
+    }
+    page
+}
+
+fn index_page(summaries: &[Summary]) -> String {
+    let mut page = format!(
+        "# Advent of Code {} write-ups\n\n| Day | Stars | Part 1 | Part 2 | Documented items |\n|---|---|---|---|---|\n",
+        config().year()
+    );
+    for summary in summaries {
+        let stars = summary
+            .parts
+            .iter()
+            .filter(|report| report.status() == "verified")
+            .count();
+        let part = |part: u8| {
+            summary
+                .parts
+                .iter()
+                .find(|report| report.part == part)
+                .map_or_else(
+                    || String::from("-"),
+                    |report| format!("{}, {:.2?}", report.status(), report.elapsed),
+                )
+        };
+        page += &format!(
+            "| [Day {0}](day{0}.md) | {1} | {2} | {3} | {4} |\n",
+            summary.day,
+            "★".repeat(stars),
+            part(1),
+            part(2),
+            summary.documented
+        );
+    }
+    page
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Writes the page of one day, or of every day and the index, to `--out` or `target/writeup`
+pub fn main(args: &Args) -> Result<ExitCode, String> {
+    let days = match args.positional(1) {
+        Some(day) => vec![parse_day(day).ok_or_else(|| format!("Invalid day: {}", day))?],
+        None => registry::DAYS.iter().map(|day| day.day).collect(),
+    };
+    let out_dir = args
+        .option("out")
+        .map_or_else(|| config().root.join(DEFAULT_OUT_DIR), PathBuf::from);
+    std::fs::create_dir_all(&out_dir).map_err(|err| format!("{}: {}", out_dir.display(), err))?;
+    let profile = Profile::from_args(args)?;
+    let baseline = load_baseline(Path::new(BASELINE_PATH));
+
+    let mut summaries = vec![];
+    for &day in &days {
+        eprintln!("Day {}: running", day);
+        let source_path = config().src_dir().join(format!("day{}.rs", day));
+        let source = std::fs::read_to_string(&source_path)
+            .map_err(|err| format!("{}: {}", source_path.display(), err))?;
+        let timeout = crate::timeout(args, day)?;
+        let prepared = Prepared::new(day, &profile.read_input(day)?)?;
+        let parts = run_parts(day, &profile, &prepared, timeout, &baseline)?;
+        let visualization = registry::visualizer(day).and_then(|visualizer| {
+            let prepared = prepared.clone();
+            // guarded like the parts, a visualizer that fails leaves the page without one
+            match guarded(timeout, move |_| {
+                Outcome::Answer((visualizer.visualize)(prepared.parsed()))
+            }) {
+                Outcome::Answer(visualization) => Some(visualization),
+                outcome => {
+                    eprintln!("Day {}: no visualization, {}", day, outcome);
+                    None
+                }
+            }
+        });
+
+        write(
+            &out_dir.join(format!("day{}.md", day)),
+            &day_page(day, &source, &parts, visualization),
+        )?;
+        summaries.push(Summary {
+            day,
+            parts,
+            documented: writeup::item_docs(&source).len(),
+        });
+    }
+
+    // a single day leaves the index of the last full run alone
+    if args.positional(1).is_none() {
+        write(&out_dir.join("index.md"), &index_page(&summaries))?;
+    }
+    println!("Wrote {} pages to {}", summaries.len(), out_dir.display());
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,97 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Returned by [`Budget::check`] once the budget ran out or was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exhausted;
+
+impl core::fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "time budget exhausted")
+    }
+}
+
+impl core::error::Error for Exhausted {}
+
+/// A time limit and cancellation token that long-running solvers check periodically
+#[derive(Clone, Debug)]
+pub struct Budget {
+    /// there is no clock without `std`, only cancelling ends the budget then
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Cancels the [`Budget`] it was created from, e.g. from another thread
+#[derive(Clone, Debug)]
+pub struct CancelHandle(Arc<AtomicBool>);
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Budget {
+            #[cfg(feature = "std")]
+            deadline: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Budget {
+            deadline: Some(Instant::now() + timeout),
+            ..Budget::unlimited()
+        }
+    }
+
+    pub fn cancel_handle(&self) -> CancelHandle {
+        CancelHandle(self.cancelled.clone())
+    }
+
+    /// Err once the deadline passed or the budget got cancelled
+    pub fn check(&self) -> Result<(), Exhausted> {
+        if self.cancelled.load(Ordering::Relaxed) || self.timed_out() {
+            Err(Exhausted)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn timed_out(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn timed_out(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn unlimited_never_exhausts() {
+    assert_eq!(Budget::unlimited().check(), Ok(()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn timeout_exhausts() {
+    let budget = Budget::with_timeout(Duration::ZERO);
+    assert_eq!(budget.check(), Err(Exhausted));
+}
+
+#[test]
+fn cancel_exhausts_all_clones() {
+    let budget = Budget::unlimited();
+    let clone = budget.clone();
+    budget.cancel_handle().cancel();
+    assert_eq!(clone.check(), Err(Exhausted));
+}
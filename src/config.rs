@@ -0,0 +1,391 @@
+//! `aoc.toml`, the project configuration, searched for from the current directory upward
+//!
+//! ```toml
+//! year = 2021
+//! input_dir = "input"          # also src_dir, template_dir and plugin_dir
+//! key_path = ".input-key"      # the key of sealed inputs
+//! default_profile = "alice"
+//! session_token = "~/.config/aoc/session"
+//! output = "json"              # or "plain"
+//! timeout = "10s"
+//!
+//! [timeouts]
+//! day23 = "2m"
+//! ```
+//!
+//! Relative paths are relative to the directory of `aoc.toml`. A small built-in parser handles
+//! the subset of TOML this needs: `[table]` headers, `key = value` lines with strings, integers
+//! and booleans, and `#` comments.
+//!
+//! Only uses `std`, `build.rs` includes this file as well.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+}
+
+/// The values of a config file, keys in tables are prefixed with the table name,
+/// e.g. `timeouts.day23`
+pub fn parse(content: &str) -> Result<BTreeMap<String, Value>, String> {
+    let mut values = BTreeMap::new();
+    let mut table = String::new();
+
+    for (idx, line) in content.lines().enumerate() {
+        let error = |message: &str| format!("line {}: {}", idx + 1, message);
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[') {
+            let name = name
+                .strip_suffix(']')
+                .ok_or_else(|| error("missing ] after table name"))?;
+            if !is_bare_key(name.trim()) {
+                return Err(error("invalid table name"));
+            }
+            table = format!("{}.", name.trim());
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| error("expected key = value"))?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(error("invalid key"));
+        }
+        let value = parse_value(value.trim()).map_err(|message| error(&message))?;
+        if values.insert(format!("{}{}", table, key), value).is_some() {
+            return Err(error(&format!("duplicate key {}", key)));
+        }
+    }
+
+    Ok(values)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// the line without a `#` comment, a `#` inside a string does not start one
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..idx],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    if let Some(string) = value.strip_prefix('"') {
+        let string = string
+            .strip_suffix('"')
+            .ok_or_else(|| String::from("unterminated string"))?;
+        let mut unescaped = String::new();
+        let mut chars = string.chars();
+        while let Some(c) = chars.next() {
+            unescaped.push(match c {
+                '\\' => match chars.next() {
+                    Some('\\') => '\\',
+                    Some('"') => '"',
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    _ => return Err(String::from("invalid escape in string")),
+                },
+                '"' => return Err(String::from("unexpected \" in string")),
+                c => c,
+            });
+        }
+        return Ok(Value::String(unescaped));
+    }
+
+    match value {
+        "true" => Ok(Value::Boolean(true)),
+        "false" => Ok(Value::Boolean(false)),
+        _ => value
+            .replace('_', "")
+            .parse()
+            .map(Value::Integer)
+            .map_err(|_| format!("invalid value {}", value)),
+    }
+}
+
+/// parse durations like `500ms`, `10s` or `2m`, a plain number is taken as seconds
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let unit_start = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (amount, unit) = text.split_at(unit_start);
+    let amount = amount.parse().ok()?;
+    match unit {
+        "ms" => Some(Duration::from_millis(amount)),
+        "" | "s" => Some(Duration::from_secs(amount)),
+        "m" => amount.checked_mul(60).map(Duration::from_secs),
+        _ => None,
+    }
+}
+
+pub const OUTPUT_FORMATS: &[&str] = &["plain", "json"];
+
+/// what a setting's value has to look like
+#[derive(Clone, Copy)]
+enum Kind {
+    String,
+    Integer,
+    Duration,
+    OutputFormat,
+}
+
+const SETTINGS: &[(&str, Kind)] = &[
+    ("year", Kind::Integer),
+    ("input_dir", Kind::String),
+    ("src_dir", Kind::String),
+    ("template_dir", Kind::String),
+    ("plugin_dir", Kind::String),
+    ("key_path", Kind::String),
+    ("default_profile", Kind::String),
+    ("session_token", Kind::String),
+    ("output", Kind::OutputFormat),
+    ("timeout", Kind::Duration),
+];
+
+/// rejects unknown keys, to catch typos, and values of the wrong kind
+fn validate(values: &BTreeMap<String, Value>) -> Result<(), String> {
+    for (key, value) in values {
+        let kind = match key.strip_prefix("timeouts.") {
+            Some(day) => day
+                .strip_prefix("day")
+                .and_then(|day| day.parse::<u8>().ok())
+                .map(|_| Kind::Duration),
+            None => SETTINGS
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|&(_, kind)| kind),
+        }
+        .ok_or_else(|| format!("unknown setting {}", key))?;
+
+        let valid = match (kind, value) {
+            (Kind::String, Value::String(_)) | (Kind::Integer, Value::Integer(_)) => true,
+            (Kind::Duration, Value::String(duration)) => parse_duration(duration).is_some(),
+            (Kind::OutputFormat, Value::String(format)) => OUTPUT_FORMATS.contains(&&**format),
+            _ => false,
+        };
+        if !valid {
+            let expected = match kind {
+                Kind::String => String::from("a string"),
+                Kind::Integer => String::from("an integer"),
+                Kind::Duration => String::from("a duration like \"500ms\", \"10s\" or \"2m\""),
+                Kind::OutputFormat => format!("one of {:?}", OUTPUT_FORMATS),
+            };
+            return Err(format!("{} must be {}", key, expected));
+        }
+    }
+    Ok(())
+}
+
+/// The project configuration, all settings are optional
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// where `aoc.toml` was found, `None` when running on the defaults
+    pub path: Option<PathBuf>,
+    /// the directory relative paths are resolved against
+    pub root: PathBuf,
+    values: BTreeMap<String, Value>,
+}
+
+impl Config {
+    /// `aoc.toml` in `start` or the closest of its parent directories
+    pub fn find(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(FILE_NAME))
+            .find(|path| path.is_file())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content =
+            std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let values = parse(&content)
+            .and_then(|values| validate(&values).map(|_| values))
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+        Ok(Config {
+            path: Some(path.to_path_buf()),
+            root: path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            values,
+        })
+    }
+
+    /// The config of this checkout, for tests
+    #[cfg(test)]
+    pub fn of_crate() -> Self {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        Config::discover(root, root).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// The config found from `start` upward, or else from `fallback_root` upward,
+    /// or the defaults rooted at `fallback_root`
+    pub fn discover(start: &Path, fallback_root: &Path) -> Result<Self, String> {
+        match Config::find(start).or_else(|| Config::find(fallback_root)) {
+            Some(path) => Config::load(&path),
+            None => Ok(Config {
+                root: fallback_root.to_path_buf(),
+                ..Config::default()
+            }),
+        }
+    }
+
+    fn string(&self, key: &str) -> Option<&str> {
+        match self.values.get(key) {
+            Some(Value::String(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// the path setting resolved against the root, `~/` is the home directory
+    fn path(&self, key: &str) -> Option<PathBuf> {
+        self.string(key).map(|path| match path.strip_prefix("~/") {
+            Some(path) => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(path),
+            None => self.root.join(path),
+        })
+    }
+
+    pub fn input_dir(&self) -> PathBuf {
+        self.path("input_dir")
+            .unwrap_or_else(|| self.root.join("input"))
+    }
+
+    pub fn src_dir(&self) -> PathBuf {
+        self.path("src_dir")
+            .unwrap_or_else(|| self.root.join("src"))
+    }
+
+    pub fn template_dir(&self) -> PathBuf {
+        self.path("template_dir")
+            .unwrap_or_else(|| self.root.join("template"))
+    }
+
+    /// where the runner loads solver plugins from, see `aoc2021::plugin`
+    pub fn plugin_dir(&self) -> PathBuf {
+        self.path("plugin_dir")
+            .unwrap_or_else(|| self.root.join("plugins"))
+    }
+
+    /// the key of sealed inputs, see `aoc2021::seal`
+    pub fn key_path(&self) -> PathBuf {
+        self.path("key_path")
+            .unwrap_or_else(|| self.root.join(".input-key"))
+    }
+
+    pub fn year(&self) -> i64 {
+        match self.values.get("year") {
+            Some(Value::Integer(year)) => *year,
+            _ => 2021,
+        }
+    }
+
+    pub fn default_profile(&self) -> Option<&str> {
+        self.string("default_profile")
+    }
+
+    /// the file holding the adventofcode.com session cookie
+    pub fn session_token(&self) -> Option<PathBuf> {
+        self.path("session_token")
+    }
+
+    /// one of [`OUTPUT_FORMATS`], `plain` by default
+    pub fn output(&self) -> &str {
+        self.string("output").unwrap_or(OUTPUT_FORMATS[0])
+    }
+
+    /// `timeout`, for days without their own
+    pub fn default_timeout(&self) -> Option<Duration> {
+        self.string("timeout").and_then(parse_duration)
+    }
+
+    /// `timeouts.dayN`, or else `timeout`
+    pub fn timeout(&self, day: u8) -> Option<Duration> {
+        self.string(&format!("timeouts.day{}", day))
+            .and_then(parse_duration)
+            .or_else(|| self.default_timeout())
+    }
+}
+
+#[test]
+fn parse_config() {
+    let values = parse(
+        "# comment\nyear = 2_021\nname = \"a # b\\\"\" # trailing\n\n[timeouts]\nday23 = \"2m\"\nfast = true\n",
+    )
+    .unwrap();
+    assert_eq!(values["year"], Value::Integer(2021));
+    assert_eq!(values["name"], Value::String(String::from("a # b\"")));
+    assert_eq!(values["timeouts.day23"], Value::String(String::from("2m")));
+    assert_eq!(values["timeouts.fast"], Value::Boolean(true));
+}
+
+#[test]
+fn parse_errors_name_line() {
+    assert_eq!(
+        parse("year = 2021\nyear = 2022").unwrap_err(),
+        "line 2: duplicate key year"
+    );
+    assert_eq!(
+        parse("\n[timeouts").unwrap_err(),
+        "line 2: missing ] after table name"
+    );
+    assert_eq!(
+        parse("name = \"open").unwrap_err(),
+        "line 1: unterminated string"
+    );
+}
+
+#[test]
+fn validate_settings() {
+    let check = |content| parse(content).and_then(|values| validate(&values));
+    assert_eq!(check("year = 2021\n[timeouts]\nday23 = \"2m\""), Ok(()));
+    assert_eq!(
+        check("yaer = 2021"),
+        Err(String::from("unknown setting yaer"))
+    );
+    assert_eq!(
+        check("year = \"2021\""),
+        Err(String::from("year must be an integer"))
+    );
+    assert!(check("timeout = \"soon\"").is_err());
+    assert_eq!(
+        check("timeout = \"999999999999999999m\""),
+        Err(String::from(
+            "timeout must be a duration like \"500ms\", \"10s\" or \"2m\""
+        ))
+    );
+    assert!(check("output = \"xml\"").is_err());
+}
+
+#[test]
+fn day_timeout_overrides_default() {
+    let config = Config {
+        values: parse("timeout = \"10s\"\n[timeouts]\nday23 = \"2m\"").unwrap(),
+        ..Config::default()
+    };
+    assert_eq!(config.timeout(23), Some(Duration::from_secs(120)));
+    assert_eq!(config.timeout(1), Some(Duration::from_secs(10)));
+    assert_eq!(config.year(), 2021);
+}
@@ -1,11 +1,16 @@
-use std::collections::VecDeque;
+use crate::parser::{self, integer, lines};
+use crate::prelude::*;
+use crate::rng::Rng;
+use alloc::collections::VecDeque;
 
-fn parse_input(input: &str) -> impl Iterator<Item = u32> + '_ {
-    input.lines().map(|line| line.parse().unwrap())
+pub type Input = Vec<u32>;
+
+pub fn parse(input: &str) -> Input {
+    parser::parse_or_panic(lines(integer()), input)
 }
 
-pub fn both(input: &str, window_size: usize) -> u32 {
-    let mut iter = parse_input(input);
+pub fn both(input: &[u32], window_size: usize) -> u32 {
+    let mut iter = input.iter().copied();
 
     let mut window = VecDeque::with_capacity(window_size);
 
@@ -25,34 +30,38 @@ pub fn both(input: &str, window_size: usize) -> u32 {
     counter
 }
 
-pub fn part1(input: &str) -> u32 {
-    both(input, 1)
+/// sums up every window instead of only comparing the values entering and leaving the window
+pub fn both_naive(input: &[u32], window_size: usize) -> u32 {
+    let sums = input
+        .windows(window_size)
+        .map(|window| window.iter().sum())
+        .collect::<Vec<u32>>();
+    sums.windows(2).filter(|pair| pair[1] > pair[0]).count() as u32
 }
 
-pub fn part2(input: &str) -> u32 {
-    both(input, 3)
+pub fn part1(input: &Input) -> u32 {
+    both(input, 1)
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day1.example.txt"));
-    assert_eq!(part1(input), 7);
+pub fn part2(input: &Input) -> u32 {
+    both(input, 3)
 }
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day1.txt"));
-    assert_eq!(part1(input), 1292);
+pub fn part1_naive(input: &Input) -> u32 {
+    both_naive(input, 1)
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day1.example.txt");
-    assert_eq!(part2(input), 5);
+pub fn part2_naive(input: &Input) -> u32 {
+    both_naive(input, 3)
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day1.txt"));
-    assert_eq!(part2(input), 1262);
+/// a random walk of `size` depth readings
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut depth = rng.range(100..200);
+    let mut input = String::new();
+    for _ in 0..size {
+        depth = (depth + rng.range(0..41)).saturating_sub(20);
+        input += &format!("{}\n", depth);
+    }
+    input
 }
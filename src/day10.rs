@@ -1,14 +1,16 @@
 use crate::day10::Delimiter::{AngleBracket, Brace, Bracket, Parenthesis};
 use crate::day10::Side::{Close, Open};
+use crate::parser::{self, lines, many1, one_of};
+use crate::prelude::*;
 
-#[derive(Debug)]
-enum Side {
+#[derive(Clone, Debug)]
+pub enum Side {
     Open(Delimiter),
     Close(Delimiter),
 }
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
-enum Delimiter {
+pub enum Delimiter {
     Parenthesis,
     Bracket,
     Brace,
@@ -34,28 +36,30 @@ impl Delimiter {
     }
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = impl Iterator<Item = Side> + '_> + '_ {
-    input.lines().map(|line| {
-        line.chars().flat_map(|char| match char {
-            '(' => Some(Open(Parenthesis)),
-            '[' => Some(Open(Bracket)),
-            '{' => Some(Open(Brace)),
-            '<' => Some(Open(AngleBracket)),
-            ')' => Some(Close(Parenthesis)),
-            ']' => Some(Close(Bracket)),
-            '}' => Some(Close(Brace)),
-            '>' => Some(Close(AngleBracket)),
-            _ => None,
-        })
-    })
+pub type Input = Vec<Vec<Side>>;
+
+const SIDES: &[(&str, Side)] = &[
+    ("(", Open(Parenthesis)),
+    ("[", Open(Bracket)),
+    ("{", Open(Brace)),
+    ("<", Open(AngleBracket)),
+    (")", Close(Parenthesis)),
+    ("]", Close(Bracket)),
+    ("}", Close(Brace)),
+    (">", Close(AngleBracket)),
+];
+
+pub fn parse(input: &str) -> Input {
+    parser::parse_or_panic(lines(many1(one_of(SIDES))), input)
 }
 
-pub fn part1(input: &str) -> usize {
-    parse_input(input)
+pub fn part1(input: &Input) -> usize {
+    input
+        .iter()
         .map(|line| {
             let mut stack = vec![];
             for delim in line {
-                match delim {
+                match *delim {
                     Open(delim) => stack.push(delim),
                     Close(delim) => {
                         if Some(delim) != stack.pop() {
@@ -69,12 +73,13 @@ pub fn part1(input: &str) -> usize {
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-    let mut completions: Vec<_> = parse_input(input)
+pub fn part2(input: &Input) -> usize {
+    let mut completions: Vec<_> = input
+        .iter()
         .flat_map(|line| {
             let mut stack = vec![];
             for delim in line {
-                match delim {
+                match *delim {
                     Open(delim) => stack.push(delim),
                     Close(delim) => {
                         if Some(delim) != stack.pop() {
@@ -98,27 +103,3 @@ pub fn part2(input: &str) -> usize {
     completions.sort_unstable();
     completions[completions.len() / 2]
 }
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day10.example.txt"));
-    assert_eq!(part1(input), 2 * 3 + 57 + 1197 + 25137);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day10.txt"));
-    assert_eq!(part1(input), 318081);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day10.example.txt");
-    assert_eq!(part2(input), 288957);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day10.txt"));
-    assert_eq!(part2(input), 4361305341);
-}
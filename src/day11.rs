@@ -1,70 +1,40 @@
-use std::collections::HashSet;
-
-fn parse_input(input: &str) -> [[u8; 10]; 10] {
-    let mut lines = input.lines();
-    [(); 10].map(|_| {
-        let mut line = lines.next().unwrap().trim().chars();
-        [(); 10].map(|_| line.next().unwrap() as u8 - b'0')
-    })
-}
+use crate::grid::Grid;
+use crate::prelude::*;
+use crate::repl::{arg, Command, Repl, Session};
 
-pub fn neighbours(x: isize, y: isize, map: &[[u8; 10]; 10]) -> Vec<(usize, usize)> {
-    let mut elems = vec![];
-
-    for x_off in -1..=1 {
-        for y_off in -1..=1 {
-            let x_idx = x + x_off;
-            let y_idx = y + y_off;
-            if (x, y) != (x + x_off, y + y_off)
-                && (0..map.len() as isize).contains(&x_idx)
-                && (0..map[x_idx as usize].len() as isize).contains(&y_idx)
-            {
-                elems.push((x_idx as usize, y_idx as usize));
-            }
-        }
-    }
+pub type Input = Grid<u8>;
 
-    elems
+pub fn parse(input: &str) -> Input {
+    Grid::parse(input, |char| char as u8 - b'0')
 }
 
-fn iterate(input: &mut [[u8; 10]; 10]) -> usize {
-    input
-        .iter_mut()
-        .flat_map(|row| row.iter_mut())
-        .for_each(|elem| *elem += 1);
+fn iterate(input: &mut Grid<u8>) -> usize {
+    input.values_mut().for_each(|elem| *elem += 1);
 
     let mut to_flash = Vec::with_capacity(100);
-    let mut flashed = HashSet::with_capacity(100);
+    let mut flashed = Set::new();
 
-    let ready = input.iter().enumerate().flat_map(|(x, row)| {
-        row.iter()
-            .enumerate()
-            .filter(|(_, &v)| v > 9)
-            .map(move |(y, _)| (x, y))
-    });
-
-    to_flash.extend(ready);
+    to_flash.extend(input.iter().filter(|(_, &v)| v > 9).map(|(pos, _)| pos));
 
     while let Some(elem) = to_flash.pop() {
         if flashed.insert(elem) {
-            let mut neighbours = neighbours(elem.0 as isize, elem.1 as isize, input);
-            neighbours.iter().for_each(|&(x, y)| input[x][y] += 1);
-            neighbours.retain(|elem| input[elem.0][elem.1] > 9);
+            let mut neighbours = input.neighbours8(elem).collect::<Vec<_>>();
+            neighbours.iter().for_each(|&pos| input[pos] += 1);
+            neighbours.retain(|&pos| input[pos] > 9);
             to_flash.extend(neighbours)
         }
     }
 
     input
-        .iter_mut()
-        .flat_map(|row| row.iter_mut())
+        .values_mut()
         .filter(|elem| **elem > 9)
         .for_each(|elem| *elem = 0);
 
     flashed.len()
 }
 
-pub fn part1(input: &str) -> usize {
-    let mut input = parse_input(input);
+pub fn part1(input: &Input) -> usize {
+    let mut input = input.clone();
     let mut flash_count = 0;
 
     for _ in 0..100 {
@@ -73,38 +43,80 @@ pub fn part1(input: &str) -> usize {
     flash_count
 }
 
-pub fn part2(input: &str) -> u32 {
-    let mut input = parse_input(input);
+pub fn part2(input: &Input) -> u32 {
+    let mut input = input.clone();
     let mut iteration_count = 0;
 
     loop {
         iteration_count += 1;
-        if iterate(&mut input) == 100 {
+        if iterate(&mut input) == input.width() * input.height() {
             return iteration_count;
         }
     }
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day11.example.txt"));
-    assert_eq!(part1(input), 1656);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day11.txt"));
-    assert_eq!(part1(input), 1640);
+/// the energy levels one step before all octopuses flash at once
+pub fn visualize(input: &Input) -> String {
+    let mut grid = input.clone();
+    for _ in 1..part2(input) {
+        iterate(&mut grid);
+    }
+    grid.to_string()
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day11.example.txt");
-    assert_eq!(part2(input), 195);
+struct Cavern {
+    initial: Input,
+    grid: Input,
+    steps: usize,
+    flashes: usize,
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day11.txt"));
-    assert_eq!(part2(input), 312);
+const COMMANDS: &[Command<Cavern>] = &[
+    Command {
+        name: "step",
+        args: "[count]",
+        help: "do the next steps, one by default, and show the grid",
+        run: |cavern, args| {
+            let count: usize = arg(args, 0, 1)?;
+            let mut flashes = 0;
+            for _ in 0..count {
+                flashes += iterate(&mut cavern.grid);
+            }
+            cavern.steps += count;
+            cavern.flashes += flashes;
+            Ok(format!(
+                "step {}: {} flashes, {} in total\n{}",
+                cavern.steps, flashes, cavern.flashes, cavern.grid
+            ))
+        },
+    },
+    Command {
+        name: "show",
+        args: "",
+        help: "show the grid",
+        run: |cavern, _| Ok(cavern.grid.to_string()),
+    },
+    Command {
+        name: "reset",
+        args: "",
+        help: "go back to the start",
+        run: |cavern, _| {
+            cavern.grid = cavern.initial.clone();
+            cavern.steps = 0;
+            cavern.flashes = 0;
+            Ok(cavern.grid.to_string())
+        },
+    },
+];
+
+pub fn repl(input: Input) -> Box<dyn Session> {
+    Box::new(Repl::new(
+        Cavern {
+            grid: input.clone(),
+            initial: input,
+            steps: 0,
+            flashes: 0,
+        },
+        COMMANDS,
+    ))
 }
@@ -1,19 +1,47 @@
-use std::collections::HashMap;
-
-pub struct Graph<'a> {
-    edges: HashMap<&'a str, Vec<&'a str>>,
+use crate::budget::{Budget, Exhausted};
+use crate::parser::{self, lines, literal, take_while1, Parser};
+use crate::prelude::*;
+use crate::search::Search;
+
+/// The caves by index, small caves are visited as bits of a `u64`
+pub struct Graph {
+    edges: Vec<Vec<usize>>,
+    small: Vec<bool>,
+    start: usize,
+    end: usize,
 }
 
-fn parse_input(input: &str) -> Graph<'_> {
-    let edges = input.lines().flat_map(|elem| elem.split_once("-")).fold(
-        HashMap::new(),
-        |mut map: HashMap<_, Vec<_>>, elem| {
-            map.entry(elem.0).or_default().push(elem.1);
-            map.entry(elem.1).or_default().push(elem.0);
-            map
-        },
-    );
-    Graph { edges }
+pub type Input = Graph;
+
+pub fn parse(input: &str) -> Input {
+    let mut names = Map::new();
+    let mut edges: Vec<Vec<usize>> = vec![];
+    let mut small = vec![];
+    let mut index = |name: &str| {
+        *names.entry(name.to_string()).or_insert_with(|| {
+            edges.push(vec![]);
+            small.push(name.chars().next().unwrap().is_lowercase());
+            edges.len() - 1
+        })
+    };
+    let cave = || take_while1("a cave name", char::is_alphabetic);
+    let pairs = parser::parse_or_panic(lines(cave().skip(literal("-")).and(cave())), input)
+        .into_iter()
+        .map(|(a, b)| (index(a), index(b)))
+        .collect::<Vec<_>>();
+    let (start, end) = (index("start"), index("end"));
+    assert!(edges.len() <= 64, "more than 64 caves");
+
+    for (a, b) in pairs {
+        edges[a].push(b);
+        edges[b].push(a);
+    }
+    Graph {
+        edges,
+        small,
+        start,
+        end,
+    }
 }
 
 pub enum SmallCaveDuplicateStrategy {
@@ -21,120 +49,59 @@ pub enum SmallCaveDuplicateStrategy {
     AtMostOneDuplicateInTotal,
 }
 
-pub fn no_duplicates<'a>(graph: &Graph<'a>, path: &mut Vec<&'a str>) -> u32 {
-    let mut unexplored_branches =
-        Vec::from([graph.edges.get(path.last().unwrap()).unwrap().as_slice()]);
-
-    let mut count = 0;
-
-    while let Some(branches) = unexplored_branches.pop() {
-        match branches {
-            [] => {
-                path.pop();
-            }
-            &[head, ref tail @ ..] => {
-                unexplored_branches.push(tail);
-
-                if head == "end" {
-                    count += 1;
-                } else if head.chars().next().unwrap().is_uppercase() || !path.contains(&head) {
-                    path.push(head);
-                    unexplored_branches.push(graph.edges.get(head).unwrap())
-                }
-            }
-        }
-    }
-
-    count
-}
-
-pub fn at_most_one_duplicate<'a>(graph: &Graph<'a>, path: &mut Vec<&'a str>) -> u32 {
-    let mut unexplored_branches = Vec::from([graph.edges.get("start").unwrap().as_slice()]);
-
-    let mut count = 0;
-
-    while let Some(branches) = unexplored_branches.pop() {
-        match branches {
-            [] => {
-                path.pop();
-            }
-            &[head, ref tail @ ..] => {
-                unexplored_branches.push(tail);
-
-                if head == "end" {
-                    count += 1;
-                } else if head == "start" {
-                } else {
-                    let uppercase = head.chars().next().unwrap().is_uppercase();
-                    let contained = path.contains(&head);
-                    path.push(head);
-                    if uppercase || !contained {
-                        unexplored_branches.push(graph.edges.get(head).unwrap())
-                    } else {
-                        count += no_duplicates(graph, path);
-                    }
-                }
+/// The current cave, the small caves visited so far and whether one of them was visited twice
+type PathState = (usize, u64, bool);
+
+/// Counts the paths from start to end, two big caves must not be connected
+pub fn count_paths(
+    graph: &Graph,
+    strategy: SmallCaveDuplicateStrategy,
+    budget: &Budget,
+) -> Result<u32, Exhausted> {
+    let twice = match strategy {
+        SmallCaveDuplicateStrategy::NoDuplicates => true,
+        SmallCaveDuplicateStrategy::AtMostOneDuplicateInTotal => false,
+    };
+    let neighbours = |&(cave, visited, twice): &PathState| {
+        graph.edges[cave].iter().filter_map(move |&next| {
+            let bit = 1 << next;
+            if next == graph.start {
+                None
+            } else if !graph.small[next] {
+                Some((next, visited, twice))
+            } else if visited & bit == 0 {
+                Some((next, visited | bit, twice))
+            } else if !twice {
+                Some((next, visited, true))
+            } else {
+                None
             }
-        }
-    }
-
-    count
-}
-
-pub fn part1(input: &str) -> u32 {
-    let graph = parse_input(input);
-    no_duplicates(&graph, &mut vec!["start"])
-}
-
-pub fn part2(input: &str) -> u32 {
-    let graph = parse_input(input);
-    at_most_one_duplicate(&graph, &mut vec!["start"])
-}
-
-#[test]
-fn part1_example1() {
-    let input = include_str!(concat!("../input/day12.example1.txt"));
-    assert_eq!(part1(input), 10);
-}
-
-#[test]
-fn part1_example2() {
-    let input = include_str!(concat!("../input/day12.example2.txt"));
-    assert_eq!(part1(input), 19);
-}
-
-#[test]
-fn part1_example3() {
-    let input = include_str!(concat!("../input/day12.example3.txt"));
-    assert_eq!(part1(input), 226);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day12.txt"));
-    assert_eq!(part1(input), 4720);
+        })
+    };
+    let count = Search::new().within(budget).count_paths(
+        (graph.start, 1 << graph.start, twice),
+        neighbours,
+        |&(cave, _, _)| cave == graph.end,
+    )?;
+    Ok(count as u32)
 }
 
-#[test]
-fn part2_example1() {
-    let input = include_str!("../input/day12.example1.txt");
-    assert_eq!(part2(input), 36);
+pub fn part1_within(graph: &Input, budget: &Budget) -> Result<u32, Exhausted> {
+    count_paths(graph, SmallCaveDuplicateStrategy::NoDuplicates, budget)
 }
 
-#[test]
-fn part2_example2() {
-    let input = include_str!("../input/day12.example2.txt");
-    assert_eq!(part2(input), 103);
+pub fn part1(input: &Input) -> u32 {
+    part1_within(input, &Budget::unlimited()).unwrap()
 }
 
-#[test]
-fn part2_example3() {
-    let input = include_str!("../input/day12.example3.txt");
-    assert_eq!(part2(input), 3509);
+pub fn part2_within(graph: &Input, budget: &Budget) -> Result<u32, Exhausted> {
+    count_paths(
+        graph,
+        SmallCaveDuplicateStrategy::AtMostOneDuplicateInTotal,
+        budget,
+    )
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day12.txt"));
-    assert_eq!(part2(input), 147848);
+pub fn part2(input: &Input) -> u32 {
+    part2_within(input, &Budget::unlimited()).unwrap()
 }
@@ -1,92 +1,79 @@
-use std::collections::HashSet;
+use crate::geom::{point, Bounds, Point};
+use crate::parser::{self, integer, lines, literal, one_of, preceded, Parser};
+use crate::prelude::*;
+use crate::repl::{arg, Command, Repl, Session};
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone, Debug)]
-pub struct Dot {
-    x: u16,
-    y: u16,
-}
+pub type Dot = Point<2>;
 
 #[derive(Debug, Copy, Clone)]
 pub enum Fold {
-    X(u16),
-    Y(u16),
+    X(i64),
+    Y(i64),
 }
 
-struct Input {
-    dots: HashSet<Dot>,
+#[derive(Clone)]
+pub struct Input {
+    dots: Set<Dot>,
     folds: Vec<Fold>,
 }
 
-fn parse_input(input: &str) -> Input {
-    let mut lines = input.lines();
-    let dots = (&mut lines)
-        .take_while(|line| !line.trim().is_empty())
-        .flat_map(|line| line.split_once(','))
-        .map(|(x, y)| Dot {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        })
-        .collect();
-
-    let folds: Vec<_> = lines.flat_map(|line| line.strip_prefix("fold along ")).flat_map(|fold|fold.split_once("=")).map(|fold|match fold.0 {
-        "x" => Fold::X,
-        "y"=> Fold::Y,
-        _ => panic!("invalid fold")
-    }(fold.1.parse().unwrap())).collect();
-    Input { dots, folds }
+/// the fold along an axis, given where
+type Axis = fn(i64) -> Fold;
+
+const AXES: &[(&str, Axis)] = &[("x", Fold::X), ("y", Fold::Y)];
+
+pub fn parse(input: &str) -> Input {
+    let fold = preceded(literal("fold along "), one_of(AXES))
+        .skip(literal("="))
+        .and(integer())
+        .map(|(axis, at)| axis(at));
+    let (dots, folds) =
+        parser::parse_or_panic(lines(point()).skip(literal("\n\n")).and(lines(fold)), input);
+    Input {
+        dots: dots.into_iter().collect(),
+        folds,
+    }
 }
 
-pub fn apply_fold(dots: &mut HashSet<Dot>, fold: Fold) {
-    match fold {
-        Fold::X(x) => {
-            let to_map = dots
-                .iter()
-                .filter(|dot| dot.x > x)
-                .copied()
-                .collect::<Vec<_>>();
-            dots.retain(|dot| dot.x < x);
-            for dot in to_map {
-                dots.insert(Dot {
-                    x: 2 * x - dot.x,
-                    y: dot.y,
-                });
-            }
-        }
-        Fold::Y(y) => {
-            let to_map = dots
-                .iter()
-                .filter(|dot| dot.y > y)
-                .copied()
-                .collect::<Vec<_>>();
-            dots.retain(|dot| dot.y < y);
-            for dot in to_map {
-                dots.insert(Dot {
-                    x: dot.x,
-                    y: 2 * y - dot.y,
-                });
-            }
-        }
+pub fn apply_fold(dots: &mut Set<Dot>, fold: Fold) {
+    let (axis, line) = match fold {
+        Fold::X(x) => (0, x),
+        Fold::Y(y) => (1, y),
+    };
+    let to_map = dots
+        .iter()
+        .filter(|dot| dot[axis] > line)
+        .copied()
+        .collect::<Vec<_>>();
+    dots.retain(|dot| dot[axis] < line);
+    for mut dot in to_map {
+        dot[axis] = 2 * line - dot[axis];
+        dots.insert(dot);
     }
 }
 
-pub fn part1(input: &str) -> usize {
-    let mut input = parse_input(input);
+pub fn part1(input: &Input) -> usize {
+    let mut input = input.clone();
     apply_fold(&mut input.dots, *input.folds.first().unwrap());
     input.dots.len()
 }
 
-pub fn part2(input: &str) -> String {
-    let mut input = parse_input(input);
+pub fn part2(input: &Input) -> String {
+    let mut input = input.clone();
     for fold in input.folds.into_iter() {
         apply_fold(&mut input.dots, fold);
     }
-    let max_x = input.dots.iter().map(|elem| elem.x).max().unwrap();
-    let max_y = input.dots.iter().map(|elem| elem.y).max().unwrap();
+    render(&input.dots)
+}
+
+/// the sheet with `#` for dots, up to the last column and row with a dot
+fn render(dots: &Set<Dot>) -> String {
+    let max = Bounds::around(dots.iter().copied()).map_or(Point::ORIGIN, |bounds| bounds.max);
 
     let mut result = String::new();
-    for y in 0..=max_y {
-        for x in 0..=max_x {
-            if input.dots.contains(&Dot { x, y }) {
+    for y in 0..=max.y() {
+        for x in 0..=max.x() {
+            if dots.contains(&Point([x, y])) {
                 result += "#";
             } else {
                 result += ".";
@@ -97,53 +84,93 @@ pub fn part2(input: &str) -> String {
     result
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day13.example.txt"));
-    assert_eq!(part1(input), 17);
-}
+/// the widest sheet `show` prints
+const MAX_SHOWN_WIDTH: i64 = 200;
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day13.txt"));
-    assert_eq!(part1(input), 790);
+struct Sheet {
+    input: Input,
+    dots: Set<Dot>,
+    /// how many of the folds are done
+    folded: usize,
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day13.example.txt");
-    // SQUARE
-    assert_eq!(
-        part2(input),
-        String::from(
-            "\
-#####
-#...#
-#...#
-#...#
-#####
-\
-"
-        )
-    );
+impl Sheet {
+    fn show(&self) -> String {
+        let (width, height) = Bounds::around(self.dots.iter().copied())
+            .map_or((0, 0), |bounds| (bounds.max.x() + 1, bounds.max.y() + 1));
+        if width > MAX_SHOWN_WIDTH {
+            format!("{}x{} is too large to show, fold first", width, height)
+        } else {
+            render(&self.dots).trim_end().to_string()
+        }
+    }
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day13.txt"));
-    // PGHZBFJC
-    assert_eq!(
-        part2(input),
-        String::from(
-            "\
-###...##..#..#.####.###..####...##..##.
-#..#.#..#.#..#....#.#..#.#.......#.#..#
-#..#.#....####...#..###..###.....#.#...
-###..#.##.#..#..#...#..#.#.......#.#...
-#....#..#.#..#.#....#..#.#....#..#.#..#
-#.....###.#..#.####.###..#.....##...##.
-\
-"
-        )
-    );
+const COMMANDS: &[Command<Sheet>] = &[
+    Command {
+        name: "fold",
+        args: "[count]",
+        help: "do the next folds, one by default, and show the sheet",
+        run: |sheet, args| {
+            let count = arg(args, 0, 1)?;
+            let left = sheet.input.folds.len() - sheet.folded;
+            if count > left {
+                return Err(format!("only {} folds left", left));
+            }
+            let mut report = String::new();
+            for _ in 0..count {
+                let fold = sheet.input.folds[sheet.folded];
+                apply_fold(&mut sheet.dots, fold);
+                sheet.folded += 1;
+                report += &format!("{:?}: {} dots\n", fold, sheet.dots.len());
+            }
+            Ok(report + &sheet.show())
+        },
+    },
+    Command {
+        name: "show",
+        args: "",
+        help: "show the sheet",
+        run: |sheet, _| Ok(sheet.show()),
+    },
+    Command {
+        name: "folds",
+        args: "",
+        help: "list the folds, the done ones marked with *",
+        run: |sheet, _| {
+            let folds = sheet.input.folds.iter().enumerate().map(|(idx, fold)| {
+                let done = if idx < sheet.folded { "*" } else { " " };
+                format!("{} {:?}", done, fold)
+            });
+            Ok(folds.collect::<Vec<_>>().join("\n"))
+        },
+    },
+    Command {
+        name: "dots",
+        args: "",
+        help: "count the visible dots",
+        run: |sheet, _| Ok(sheet.dots.len().to_string()),
+    },
+    Command {
+        name: "reset",
+        args: "",
+        help: "unfold the sheet",
+        run: |sheet, _| {
+            sheet.dots = sheet.input.dots.clone();
+            sheet.folded = 0;
+            Ok(format!("{} dots", sheet.dots.len()))
+        },
+    },
+];
+
+pub fn repl(input: Input) -> Box<dyn Session> {
+    let dots = input.dots.clone();
+    Box::new(Repl::new(
+        Sheet {
+            input,
+            dots,
+            folded: 0,
+        },
+        COMMANDS,
+    ))
 }
@@ -1,31 +1,29 @@
-use std::collections::HashMap;
+use crate::parser::{self, lines, literal, many1, satisfy, Parser};
+use crate::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Input {
-    pairs: HashMap<(char, char), u64>,
-    char_counts: HashMap<char, u64>,
-    mappings: HashMap<(char, char), char>,
+    pairs: Map<(char, char), u64>,
+    char_counts: Map<char, u64>,
+    mappings: Map<(char, char), char>,
 }
 
-fn parse_input(input: &str) -> Input {
-    let mut lines = input.lines();
-    let sequence = lines.next().unwrap().chars().collect::<Vec<_>>();
-    let mappings = lines
-        .skip(1)
-        .flat_map(|line| line.split_once(" -> "))
-        .map(|(pat, ins)| {
-            let mut pat_chars = pat.chars();
-            (
-                (pat_chars.next().unwrap(), pat_chars.next().unwrap()),
-                ins.chars().next().unwrap(),
-            )
-        })
-        .collect();
-    let char_counts = sequence.iter().fold(HashMap::new(), |mut map, elem| {
+pub fn parse(input: &str) -> Input {
+    let element = || satisfy("an element", |c| c.is_ascii_uppercase());
+    let rule = element()
+        .and(element())
+        .skip(literal(" -> "))
+        .and(element());
+    let (sequence, rules) = parser::parse_or_panic(
+        many1(element()).skip(literal("\n\n")).and(lines(rule)),
+        input,
+    );
+    let mappings = rules.into_iter().collect();
+    let char_counts = sequence.iter().fold(Map::new(), |mut map, elem| {
         *map.entry(*elem).or_default() += 1;
         map
     });
-    let pairs = sequence.windows(2).fold(HashMap::new(), |mut map, elem| {
+    let pairs = sequence.windows(2).fold(Map::new(), |mut map, elem| {
         if let [c1, c2] = elem {
             *map.entry((*c1, *c2)).or_default() += 1;
         }
@@ -50,7 +48,7 @@ pub fn apply_mapping(input: &mut Input) {
                 vec![(*key, *count)]
             }
         })
-        .fold(HashMap::new(), |mut map, (key, count)| {
+        .fold(Map::new(), |mut map, (key, count)| {
             *map.entry(key).or_default() += count;
             map
         });
@@ -58,8 +56,8 @@ pub fn apply_mapping(input: &mut Input) {
     input.pairs = pairs;
 }
 
-pub fn both(input: &str, iterations: u32) -> u64 {
-    let mut input = parse_input(input);
+pub fn both(input: &Input, iterations: u32) -> u64 {
+    let mut input = input.clone();
     for _ in 0..iterations {
         apply_mapping(&mut input);
     }
@@ -69,34 +67,10 @@ pub fn both(input: &str, iterations: u32) -> u64 {
     max - min
 }
 
-pub fn part1(input: &str) -> u64 {
+pub fn part1(input: &Input) -> u64 {
     both(input, 10)
 }
 
-pub fn part2(input: &str) -> u64 {
+pub fn part2(input: &Input) -> u64 {
     both(input, 40)
 }
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day14.example.txt"));
-    assert_eq!(part1(input), 1749 - 161);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day14.txt"));
-    assert_eq!(part1(input), 2947);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day14.example.txt");
-    assert_eq!(part2(input), 2188189693529);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day14.txt"));
-    assert_eq!(part2(input), 3232426226464);
-}
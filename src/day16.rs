@@ -1,4 +1,8 @@
-fn parse_input(input: &str) -> impl Iterator<Item = bool> + '_ {
+use crate::parser::{self, take_while1};
+use crate::prelude::*;
+use crate::repl::{Command, Repl, Session};
+
+fn to_bits(input: &str) -> impl Iterator<Item = bool> + '_ {
     input.chars().flat_map(|c| {
         let bits = match c as u8 {
             b'0'..=b'9' => c as u8 - b'0',
@@ -57,7 +61,7 @@ pub fn parse_packet_kind(bit_stream: &mut impl Iterator<Item = bool>) -> PacketK
             None => panic!("Out of bits while reading length type ID"),
             Some(false) => {
                 let length = parse_number(bit_stream, 15);
-                let mut data = bit_stream.take(length as usize).peekable();
+                let mut data = bit_stream.take(length).peekable();
                 while data.peek().is_some() {
                     let mut box_iter: Box<dyn Iterator<Item = bool>> = Box::new(&mut data);
                     arguments.push(parse_packet(&mut box_iter))
@@ -81,6 +85,15 @@ pub fn parse_packet_kind(bit_stream: &mut impl Iterator<Item = bool>) -> PacketK
     }
 }
 
+pub type Input = Packet;
+
+pub fn parse(input: &str) -> Input {
+    let hex = take_while1("a hexadecimal digit", |c| {
+        c.is_ascii_digit() || ('A'..='F').contains(&c)
+    });
+    parse_packet(&mut to_bits(parser::parse_or_panic(hex, input)))
+}
+
 pub fn parse_packet(bit_stream: &mut impl Iterator<Item = bool>) -> Packet {
     let version = parse_number(bit_stream, 3) as u8;
     let kind = parse_packet_kind(bit_stream);
@@ -120,23 +133,19 @@ pub enum OperatorData {
     PacketCount,
 }
 
-pub fn part1(input: &str) -> u32 {
-    let mut iter = parse_input(input);
-
-    let packet = parse_packet(&mut iter);
-
+pub fn part1(packet: &Input) -> u32 {
     let mut to_process = vec![packet];
 
     let mut sum = 0;
     while let Some(Packet { version, kind }) = to_process.pop() {
-        sum += version as u32;
+        sum += *version as u32;
         match kind {
             PacketKind::Literal(_) => {}
             PacketKind::Operator {
                 op: _,
                 op_data: _,
-                mut arguments,
-            } => to_process.append(&mut arguments),
+                arguments,
+            } => to_process.extend(arguments),
         }
     }
     sum
@@ -207,114 +216,74 @@ pub fn eval(packet: &Packet) -> usize {
     }
 }
 
-pub fn part2(input: &str) -> usize {
-    let mut iter = parse_input(input);
-
-    let packet = parse_packet(&mut iter);
-
-    eval(&packet)
-}
-
-#[test]
-fn part1_example_2() {
-    let input = "D2FE28";
-    assert_eq!(part1(input), 6);
-}
-
-#[test]
-fn part1_example_1() {
-    let input = "38006F45291200";
-    assert_eq!(part1(input), 1 + 6 + 2);
+pub fn part2(packet: &Input) -> usize {
+    eval(packet)
 }
 
-#[test]
-fn part1_example0() {
-    let input = "EE00D40C823060";
-    assert_eq!(part1(input), 7 + 2 + 4 + 1);
-}
-
-#[test]
-fn part1_example1() {
-    let input = "8A004A801A8002F478";
-    assert_eq!(part1(input), 4 + 1 + 5 + 6);
-}
-
-#[test]
-fn part1_example2() {
-    let input = "620080001611562C8802118E34";
-    assert_eq!(part1(input), 3 + 9);
-}
-
-#[test]
-fn part1_example3() {
-    let input = "C0015000016115A2E0802F182340";
-    assert_eq!(part1(input), 23);
-}
-
-#[test]
-fn part1_example4() {
-    let input = "A0016C880162017C3686B18A3D4780";
-    assert_eq!(part1(input), 31);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day16.txt"));
-    assert_eq!(part1(input), 986);
-}
-
-#[test]
-fn part2_example_2() {
-    let input = "C200B40A82";
-    assert_eq!(part2(input), 1 + 2);
-}
-
-#[test]
-fn part2_example_1() {
-    let input = "04005AC33890";
-    assert_eq!(part2(input), 6 * 9);
-}
-
-#[test]
-fn part2_example0() {
-    let input = "880086C3E88112";
-    assert_eq!(part2(input), [7, 8, 9].into_iter().min().unwrap());
-}
-
-#[test]
-fn part2_example1() {
-    let input = "CE00C43D881120";
-    assert_eq!(part2(input), [7, 8, 9].into_iter().max().unwrap());
-}
-
-#[test]
-fn part2_example2() {
-    let input = "D8005AC2A8F0";
-    assert_eq!(part2(input), (5 < 15) as usize);
-}
-
-#[test]
-fn part2_example3() {
-    let input = "F600BC2D8F";
-    assert_eq!(part2(input), (5 > 15) as usize);
+/// the packet at a dot separated list of argument indices, the outermost packet for an empty path
+fn select<'a>(packet: &'a Packet, args: &[&str]) -> Result<&'a Packet, String> {
+    let path = match args.first() {
+        Some(path) => *path,
+        None => return Ok(packet),
+    };
+    path.split('.').try_fold(packet, |packet, idx| {
+        let arguments = match &packet.kind {
+            PacketKind::Literal(_) => return Err(format!("{} is inside a literal", path)),
+            PacketKind::Operator { arguments, .. } => arguments,
+        };
+        idx.parse::<usize>()
+            .ok()
+            .and_then(|idx| arguments.get(idx))
+            .ok_or_else(|| format!("no argument {} in {}", idx, path))
+    })
 }
 
-#[test]
-fn part2_example4() {
-    let input = "9C005AC2F8F0";
-    assert_eq!(part2(input), (5 == 15) as usize);
+fn tree(packet: &Packet, depth: usize, lines: &mut Vec<String>) {
+    let indent = "  ".repeat(depth);
+    match &packet.kind {
+        PacketKind::Literal(value) => {
+            lines.push(format!("{}v{} literal {}", indent, packet.version, value))
+        }
+        PacketKind::Operator { op, arguments, .. } => {
+            lines.push(format!(
+                "{}v{} {:?} = {}",
+                indent,
+                packet.version,
+                op,
+                eval(packet)
+            ));
+            for argument in arguments {
+                tree(argument, depth + 1, lines);
+            }
+        }
+    }
 }
 
-#[test]
-fn part2_example5() {
-    let input = "9C0141080250320F1802104A08";
-    #[allow(clippy::eq_op)]
-    let result = (1 + 3 == 2 * 2) as usize;
-    assert_eq!(part2(input), result);
-}
+const COMMANDS: &[Command<Packet>] = &[
+    Command {
+        name: "tree",
+        args: "[path]",
+        help: "show the packets, a path like 0.2 picks the third argument of the first",
+        run: |packet, args| {
+            let mut lines = vec![];
+            tree(select(packet, args)?, 0, &mut lines);
+            Ok(lines.join("\n"))
+        },
+    },
+    Command {
+        name: "eval",
+        args: "[path]",
+        help: "evaluate a packet",
+        run: |packet, args| Ok(eval(select(packet, args)?).to_string()),
+    },
+    Command {
+        name: "versions",
+        args: "[path]",
+        help: "sum the versions of a packet and the packets inside it",
+        run: |packet, args| Ok(part1(select(packet, args)?).to_string()),
+    },
+];
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day16.txt"));
-    assert_eq!(part2(input), 18234816469452);
+pub fn repl(input: Input) -> Box<dyn Session> {
+    Box::new(Repl::new(input, COMMANDS))
 }
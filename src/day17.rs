@@ -1,38 +1,30 @@
-use std::collections::HashSet;
-use std::ops::RangeInclusive;
-
-#[derive(Clone, Debug)]
-pub struct Target {
-    x: RangeInclusive<i32>,
-    y: RangeInclusive<i32>,
-}
-
-fn parse_input(input: &str) -> Target {
-    let ranges = input.strip_prefix("target area: ").unwrap();
-    let (prefixed_x_range, prefixed_y_range) = ranges.split_once(", ").unwrap();
-    let (x_range_start, x_range_end) = prefixed_x_range
-        .strip_prefix("x=")
-        .unwrap()
-        .split_once("..")
-        .unwrap();
-    let (y_range_start, y_range_end) = prefixed_y_range
-        .strip_prefix("y=")
-        .unwrap()
-        .split_once("..")
-        .unwrap();
-    Target {
-        x: x_range_start.parse().unwrap()..=x_range_end.parse().unwrap(),
-        y: y_range_start.parse().unwrap()..=y_range_end.parse().unwrap(),
-    }
+use crate::budget::{Budget, Exhausted};
+use crate::geom::{Bounds, Point};
+use crate::json::{self, Value};
+use crate::parser::{self, integer, literal, preceded, Parser};
+use crate::prelude::*;
+
+/// The target area, x to the right and y up
+pub type Target = Bounds<2>;
+
+pub type Input = Target;
+
+pub fn parse(input: &str) -> Input {
+    let range = || integer().skip(literal("..")).and(integer());
+    let target = preceded(literal("target area: x="), range())
+        .skip(literal(", y="))
+        .and(range());
+    let ((x_min, x_max), (y_min, y_max)) = parser::parse_or_panic(target, input);
+    Target::new(Point([x_min, y_min]), Point([x_max, y_max]))
 }
 
 /// For the initial y-Velocity return the maximum height reached
-pub fn max_height(initial_y_velocity: i32) -> i32 {
+pub fn max_height(initial_y_velocity: i64) -> i64 {
     (0..=initial_y_velocity).sum()
 }
 
 /// return the steps (starting with step 0) after which we in the y-range of the target area
-pub fn y_in_target_range(target: Target, initial_velocity: i32) -> impl IntoIterator<Item = i32> {
+pub fn y_in_target_range(target: &Target, initial_velocity: i64) -> impl IntoIterator<Item = i64> {
     let mut current_velocity = initial_velocity;
 
     let mut current_y = 0;
@@ -40,10 +32,10 @@ pub fn y_in_target_range(target: Target, initial_velocity: i32) -> impl IntoIter
 
     let mut valid = vec![];
 
-    while *target.y.start() <= current_y {
+    while target.min.y() <= current_y {
         current_y += current_velocity;
         current_velocity -= 1;
-        if target.y.contains(&current_y) {
+        if target.range(1).contains(&current_y) {
             valid.push(step);
         }
         step += 1;
@@ -52,63 +44,107 @@ pub fn y_in_target_range(target: Target, initial_velocity: i32) -> impl IntoIter
 }
 
 /// return the x-velocities that are in the targets x-Range after the specified step (starting with step 0)
-pub fn reaching_x_velocities(target: Target, steps: i32) -> Vec<i32> {
+pub fn reaching_x_velocities(target: &Target, steps: i64) -> Vec<i64> {
     let min_x = 0;
-    let max_x = *target.x.end();
+    let max_x = target.max.x();
 
     (min_x..=max_x)
-        .into_iter()
         .filter(|initial_x| {
             target
-                .x
+                .range(0)
                 .contains(&(0..=steps).map(|step| 0.max(initial_x - step)).sum())
         })
         .collect()
 }
 
 /// return the vectors that at some point reach the target area
-pub fn possible_vectors(target: Target) -> HashSet<(i32, i32)> {
-    let min_y = 0.min(*target.y.start());
-    let max_y = target.y.start().abs().max(target.y.end().abs());
-    (min_y..=max_y)
-        .into_iter()
-        .flat_map(|y_velocity| {
-            let step_range = y_in_target_range(target.clone(), y_velocity);
-            step_range.into_iter().map(move |step| (y_velocity, step))
-        })
-        .flat_map(|(y_velocity, steps)| {
-            let x_velocities = reaching_x_velocities(target.clone(), steps);
-            x_velocities
-                .into_iter()
-                .map(move |x_velocity| (x_velocity, y_velocity))
-        })
-        .collect::<HashSet<_>>()
+pub fn possible_vectors(target: &Target, budget: &Budget) -> Result<Set<(i64, i64)>, Exhausted> {
+    let min_y = 0.min(target.min.y());
+    let max_y = target.min.y().abs().max(target.max.y().abs());
+
+    let mut vectors = Set::new();
+    for y_velocity in min_y..=max_y {
+        for steps in y_in_target_range(target, y_velocity) {
+            budget.check()?;
+            vectors.extend(
+                reaching_x_velocities(target, steps)
+                    .into_iter()
+                    .map(|x_velocity| (x_velocity, y_velocity)),
+            );
+        }
+    }
+    Ok(vectors)
 }
 
-pub fn part1(input: &str) -> i32 {
-    possible_vectors(parse_input(input))
+pub fn part1_within(target: &Input, budget: &Budget) -> Result<i64, Exhausted> {
+    Ok(possible_vectors(target, budget)?
         .into_iter()
         .map(|(_, y_vel)| max_height(y_vel))
         .max()
-        .unwrap()
+        .unwrap())
+}
+
+pub fn part1(input: &Input) -> i64 {
+    part1_within(input, &Budget::unlimited()).unwrap()
 }
 
-pub fn part2(input: &str) -> usize {
-    possible_vectors(parse_input(input)).len()
+pub fn part2_within(target: &Input, budget: &Budget) -> Result<usize, Exhausted> {
+    Ok(possible_vectors(target, budget)?.len())
+}
+
+pub fn part2(input: &Input) -> usize {
+    part2_within(input, &Budget::unlimited()).unwrap()
+}
+
+/// every initial velocity that hits the target, the highest one for part 1
+pub fn explain(target: &Input, _part: u8) -> Value {
+    let mut vectors = possible_vectors(target, &Budget::unlimited())
+        .unwrap()
+        .into_iter()
+        .collect::<Vec<_>>();
+    vectors.sort_unstable();
+    let highest = vectors.iter().max_by_key(|(_, y_vel)| *y_vel).copied();
+
+    json::object([
+        (
+            "target",
+            json::object([
+                ("x", [target.min.x(), target.max.x()].into()),
+                ("y", [target.min.y(), target.max.y()].into()),
+            ]),
+        ),
+        (
+            "highest",
+            highest
+                .map(|(x_vel, y_vel)| {
+                    json::object([
+                        ("velocity", [x_vel, y_vel].into()),
+                        ("height", max_height(y_vel).into()),
+                    ])
+                })
+                .into(),
+        ),
+        ("count", vectors.len().into()),
+        (
+            "velocities",
+            vectors
+                .into_iter()
+                .map(|(x_vel, y_vel)| Value::from([x_vel, y_vel]))
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+    ])
 }
 
 /// Simulates with the initial vector returning whether the target area is reach after any step
 #[cfg(test)]
-fn simulate(target: Target, init_vector: (i32, i32)) -> bool {
-    let mut current_vector = init_vector;
-    let mut current_x = 0;
-    let mut current_y = 0;
-    while current_x <= *target.x.end() && *target.y.start() <= current_y {
-        current_x += current_vector.0;
-        current_y += current_vector.1;
-        current_vector.0 = 0.max(current_vector.0 - 1);
-        current_vector.1 -= 1;
-        if target.x.contains(&current_x) && target.y.contains(&current_y) {
+fn simulate(target: &Target, init_vector: (i64, i64)) -> bool {
+    let mut velocity = Point([init_vector.0, init_vector.1]);
+    let mut position = Point::ORIGIN;
+    while position.x() <= target.max.x() && target.min.y() <= position.y() {
+        position += velocity;
+        velocity = Point([0.max(velocity.x() - 1), velocity.y() - 1]);
+        if target.contains(&position) {
             return true;
         }
     }
@@ -118,42 +154,16 @@ fn simulate(target: Target, init_vector: (i32, i32)) -> bool {
 #[test]
 fn sanity_example() {
     let input = include_str!(concat!("../input/day17.example.txt"));
-    let target = parse_input(input);
-    let possible = possible_vectors(target.clone());
-    assert!(possible
-        .into_iter()
-        .all(|elem| simulate(target.clone(), elem)))
+    let target = parse(input);
+    let possible = possible_vectors(&target, &Budget::unlimited()).unwrap();
+    assert!(possible.into_iter().all(|elem| simulate(&target, elem)))
 }
 #[test]
+#[cfg_attr(sealed_without_key, ignore = "sealed inputs and no key to open them")]
 fn sanity_full() {
     let input = include_str!(concat!("../input/day17.txt"));
-    let target = parse_input(input);
-    let possible = possible_vectors(target.clone());
-    assert!(possible
-        .into_iter()
-        .all(|elem| simulate(target.clone(), elem)))
-}
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day17.example.txt"));
-    assert_eq!(part1(input), 45);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day17.txt"));
-    assert_eq!(part1(input), 2701);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day17.example.txt");
-    assert_eq!(part2(input), 112);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day17.txt"));
-    assert_eq!(part2(input), 1070);
+    let input = crate::seal::open_in_crate(input);
+    let target = parse(&crate::input::normalize(&input));
+    let possible = possible_vectors(&target, &Budget::unlimited()).unwrap();
+    assert!(possible.into_iter().all(|elem| simulate(&target, elem)))
 }
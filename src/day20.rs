@@ -1,89 +1,71 @@
-use std::fmt::{Formatter, Write};
+use crate::grid::Grid;
+use crate::parser::{self, literal, rest, take_while1, Parser};
+use crate::prelude::*;
 
+/// The image's outside value is the colour of the infinite rest of it
+#[derive(Clone)]
 pub struct Input {
     enhancement_alg: Vec<bool>,
-    image: Vec<Vec<bool>>,
-    defaul_pixel: bool,
+    image: Grid<bool>,
 }
 
-impl std::fmt::Display for Input {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in &self.image {
-            for entry in row {
-                f.write_char(if *entry { '#' } else { '.' })?;
-            }
-            f.write_char('\n')?;
-        }
-        Ok(())
+impl core::fmt::Display for Input {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let pixels = self.image.map(|&lit| if lit { '#' } else { '.' });
+        writeln!(f, "{}", pixels)
     }
 }
 
-impl Input {
-    fn get_pixel(&self, r: isize, c: isize) -> bool {
-        if r >= 0 && c >= 0 {
-            let r = r as usize;
-            let c = c as usize;
-            if let Some(row) = self.image.get(r) {
-                if let Some(p) = row.get(c) {
-                    return *p;
-                }
-            }
-        }
-        self.defaul_pixel
+fn char_to_pixel(c: char) -> bool {
+    match c {
+        '#' => true,
+        '.' => false,
+        _ => panic!("{} is not a valid pixel value", c),
     }
 }
 
-fn parse_input(input: &str) -> Input {
-    let mut lines = input.lines();
-
-    fn char_to_pixel(c: char) -> bool {
-        match c {
-            '#' => true,
-            '.' => false,
-            _ => panic!("{} is not a valid pixel value", c),
-        }
-    }
-
-    let enhancement_alg = lines.next().unwrap().chars().map(char_to_pixel).collect();
-
-    let _ = lines.next();
-
-    let image = lines
-        .map(|line| line.chars().map(char_to_pixel).collect())
-        .collect();
+pub fn parse(input: &str) -> Input {
+    let pixels = take_while1("`#` or `.`", |c| c == '#' || c == '.');
+    let (enhancement_alg, image) = parser::parse_or_panic(
+        pixels
+            .map(|pixels| pixels.chars().map(char_to_pixel).collect())
+            .skip(literal("\n\n"))
+            .and(rest()),
+        input,
+    );
 
     Input {
         enhancement_alg,
-        image,
-        defaul_pixel: false,
+        image: Grid::parse(image, char_to_pixel).with_outside(false),
     }
 }
 
 fn apply_enhancement(input: &mut Input) {
-    let mut new_image = vec![vec![false; input.image[0].len() + 2]; input.image.len() + 2];
-
-    for (idx_row, row) in new_image.iter_mut().enumerate() {
-        for (idx_col, entry) in row.iter_mut().enumerate() {
+    let image = &input.image;
+    let new_image = Grid::from_fn(
+        image.width() + 2,
+        image.height() + 2,
+        |(idx_row, idx_col)| {
             let mut lookup_idx = 0;
             for row in -1..=1 {
                 for col in -1..=1 {
                     lookup_idx <<= 1;
-                    lookup_idx |= input
-                        .get_pixel(idx_row as isize + row - 1, idx_col as isize + col - 1)
-                        as usize;
+                    lookup_idx |= *image
+                        .at(idx_row as isize + row - 1, idx_col as isize + col - 1)
+                        .unwrap() as usize;
                 }
             }
-            *entry = input.enhancement_alg[lookup_idx];
-        }
-    }
+            input.enhancement_alg[lookup_idx]
+        },
+    );
 
-    if input.defaul_pixel {
-        input.defaul_pixel = input.enhancement_alg[0b111111111]
+    let default_pixel = if image.outside() == Some(&true) {
+        input.enhancement_alg[0b111111111]
     } else {
-        input.defaul_pixel = input.enhancement_alg[0b000000000]
-    }
+        input.enhancement_alg[0b000000000]
+    };
 
-    input.image = new_image;
+    input.image = new_image.with_outside(default_pixel);
 }
 
 pub fn enhance(mut input: Input, iterations: usize) -> usize {
@@ -91,42 +73,13 @@ pub fn enhance(mut input: Input, iterations: usize) -> usize {
         apply_enhancement(&mut input)
     }
 
-    input
-        .image
-        .iter()
-        .flat_map(|elem| elem.iter())
-        .filter(|elem| **elem)
-        .count()
-}
-
-pub fn part1(input: &str) -> usize {
-    enhance(parse_input(input), 2)
-}
-
-pub fn part2(input: &str) -> usize {
-    enhance(parse_input(input), 50)
-}
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day20.example.txt"));
-    assert_eq!(part1(input), 35);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day20.txt"));
-    assert_eq!(part1(input), 5400);
+    input.image.values().filter(|elem| **elem).count()
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day20.example.txt");
-    assert_eq!(part2(input), 3351);
+pub fn part1(input: &Input) -> usize {
+    enhance(input.clone(), 2)
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day20.txt"));
-    assert_eq!(part2(input), 18989);
+pub fn part2(input: &Input) -> usize {
+    enhance(input.clone(), 50)
 }
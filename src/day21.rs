@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use crate::parser::{self, array, integer, literal, preceded, Parser};
+use crate::prelude::*;
 
-#[derive(Hash, Eq, PartialEq, Clone)]
+#[derive(Hash, Ord, PartialOrd, Eq, PartialEq, Clone)]
 pub struct Player {
     name: u16,
     position: u16,
@@ -14,39 +15,24 @@ impl Player {
     }
 }
 
-fn parse_input(input: &str) -> (Player, Player) {
-    let mut lines = input.lines();
+pub type Input = (Player, Player);
 
-    let player1 = lines.next().unwrap().splitn(5, ' ').collect::<Vec<_>>();
-    let player2 = lines.next().unwrap().splitn(5, ' ').collect::<Vec<_>>();
-    #[allow(clippy::match_ref_pats)]
-    let (p1_idx, s1_idx) = match player1.as_slice() {
-        &[_, p_idx, _, _, s_idx] => (p_idx, s_idx),
-        _ => panic!(),
-    };
-    #[allow(clippy::match_ref_pats)]
-    let (p2_idx, s2_idx) = match player2.as_slice() {
-        &[_, p_idx, _, _, s_idx] => (p_idx, s_idx),
-        _ => panic!(),
-    };
-
-    (
-        Player {
-            name: p1_idx.parse().unwrap(),
-            position: s1_idx.parse().unwrap(),
-            points: 0,
-        },
-        Player {
-            name: p2_idx.parse().unwrap(),
-            position: s2_idx.parse().unwrap(),
+pub fn parse(input: &str) -> Input {
+    let player = preceded(literal("Player "), integer())
+        .skip(literal(" starting position: "))
+        .and(integer())
+        .map(|(name, position)| Player {
+            name,
+            position,
             points: 0,
-        },
-    )
+        });
+    let [player1, player2] = parser::parse_or_panic(array(player, literal("\n")), input);
+    (player1, player2)
 }
 
-pub fn part1(input: &str) -> u32 {
+pub fn part1(input: &Input) -> u32 {
     let mut dice = (1..=100).cycle();
-    let (mut player_a, mut player_b) = parse_input(input);
+    let (mut player_a, mut player_b) = input.clone();
 
     let mut rolls = 0;
 
@@ -73,7 +59,7 @@ pub fn part1(input: &str) -> u32 {
 pub fn split_the_timeline(
     a: &Player,
     b: &Player,
-    cache: &mut HashMap<(Player, Player), (u64, u64)>,
+    cache: &mut Map<(Player, Player), (u64, u64)>,
 ) -> (u64, u64) {
     if let Some(cache_result) = cache.get(&(a.clone(), b.clone())) {
         *cache_result
@@ -102,33 +88,8 @@ pub fn split_the_timeline(
     }
 }
 
-pub fn part2(input: &str) -> u64 {
-    let (a, b) = parse_input(input);
-    let mut cache: HashMap<(Player, Player), (u64, u64)> = HashMap::new();
-    let (wins_a, wins_b) = split_the_timeline(&a, &b, &mut cache);
+pub fn part2((a, b): &Input) -> u64 {
+    let mut cache: Map<(Player, Player), (u64, u64)> = Map::new();
+    let (wins_a, wins_b) = split_the_timeline(a, b, &mut cache);
     wins_a.max(wins_b)
 }
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day21.example.txt"));
-    assert_eq!(part1(input), 745 * 993);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day21.txt"));
-    assert_eq!(part1(input), 734820);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day21.example.txt");
-    assert_eq!(part2(input), 444356092776315);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day21.txt"));
-    assert_eq!(part2(input), 193170338541590);
-}
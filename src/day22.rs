@@ -1,152 +1,55 @@
-use std::num::ParseIntError;
-use std::ops::RangeInclusive;
-use std::str::FromStr;
+use crate::geom::{Bounds, Point};
+use crate::json::{self, Value};
+use crate::parser::{self, integer, lines, literal, one_of, preceded, Parser};
+use crate::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CuboidInstruction {
     cuboid: Cuboid,
     state: TargetState,
 }
 
-impl FromStr for CuboidInstruction {
-    type Err = ();
+pub type Cuboid = Bounds<3>;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (state, cuboid) = s.split_once(' ').unwrap();
-        let state = state.parse()?;
-        let cuboid = cuboid.parse()?;
-
-        Ok(CuboidInstruction { state, cuboid })
-    }
-}
-
-#[derive(Debug)]
-pub struct Cuboid {
-    x: RangeInclusive<isize>,
-    y: RangeInclusive<isize>,
-    z: RangeInclusive<isize>,
-}
-
-impl Cuboid {
-    pub fn size(&self) -> usize {
-        self.x.clone().count() * self.y.clone().count() * self.z.clone().count()
-    }
-
-    pub fn remove_overlap(self, other: &Self) -> Vec<Self> {
-        if let Some(overlap) = self.overlaps(other) {
-            self.remove_sub_cuboid(&overlap)
-        } else {
-            vec![self]
-        }
-    }
-
-    pub fn overlaps(&self, other: &Self) -> Option<Cuboid> {
-        let cuboid = Cuboid {
-            x: *self.x.start().max(other.x.start())..=*self.x.end().min(other.x.end()),
-            y: *self.y.start().max(other.y.start())..=*self.y.end().min(other.y.end()),
-            z: *self.z.start().max(other.z.start())..=*self.z.end().min(other.z.end()),
-        };
-
-        Some(cuboid).filter(|cuboid| !cuboid.is_empty())
-    }
-
-    fn is_empty(&self) -> bool {
-        self.x.is_empty() || self.y.is_empty() || self.z.is_empty()
-    }
-
-    fn remove_sub_cuboid(&self, sub: &Self) -> Vec<Cuboid> {
-        let pre_x = Cuboid {
-            x: *self.x.start()..=(sub.x.start() - 1),
-            y: self.y.clone(),
-            z: self.z.clone(),
-        };
-        let post_x = Cuboid {
-            x: (sub.x.end() + 1)..=*self.x.end(),
-            y: self.y.clone(),
-            z: self.z.clone(),
-        };
-        let remaining_x = Cuboid {
-            x: sub.x.clone(),
-            y: self.y.clone(),
-            z: self.z.clone(),
-        };
-        let pre_y = Cuboid {
-            x: remaining_x.x.clone(),
-            y: *remaining_x.y.start()..=sub.y.start() - 1,
-            z: remaining_x.z.clone(),
-        };
-        let post_y = Cuboid {
-            x: remaining_x.x.clone(),
-            y: sub.y.end() + 1..=*remaining_x.y.end(),
-            z: remaining_x.z.clone(),
-        };
-        let remaining_y = Cuboid {
-            x: remaining_x.x,
-            y: sub.y.clone(),
-            z: remaining_x.z,
-        };
-        let pre_z = Cuboid {
-            x: remaining_y.x.clone(),
-            y: remaining_y.y.clone(),
-            z: *remaining_y.z.start()..=sub.z.start() - 1,
-        };
-        let post_z = Cuboid {
-            x: remaining_y.x,
-            y: remaining_y.y,
-            z: sub.z.end() + 1..=*remaining_y.z.end(),
-        };
-
-        let mut parts = vec![pre_x, post_x, pre_y, post_y, pre_z, post_z];
-        parts.retain(|elem| !elem.is_empty());
-        parts
-    }
+fn to_json(cuboid: &Cuboid) -> Value {
+    let range = |axis: usize| Value::from([cuboid.min[axis], cuboid.max[axis]]);
+    json::object([
+        ("x", range(0)),
+        ("y", range(1)),
+        ("z", range(2)),
+        ("size", cuboid.volume().into()),
+    ])
 }
 
-impl FromStr for Cuboid {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let ranges = s
-            .splitn(3, ',')
-            .into_iter()
-            .flat_map(|elem| elem.split_once('='))
-            .flat_map(|(_name, range)| range.split_once(".."))
-            .map(|(start, end)| Ok((start.parse()?, end.parse()?)))
-            .collect::<Result<Vec<(_, _)>, _>>()
-            .map_err(|_: ParseIntError| ())?;
-
-        if let [x, y, z] = ranges.as_slice() {
-            Ok(Cuboid {
-                x: x.0..=x.1,
-                y: y.0..=y.1,
-                z: z.0..=z.1,
-            })
-        } else {
-            Err(())
-        }
-    }
+/// `x=-20..26,y=-36..17,z=-47..7`
+fn cuboid<'a>() -> impl Parser<'a, Cuboid> {
+    let range = || integer().skip(literal("..")).and(integer());
+    preceded(literal("x="), range())
+        .skip(literal(",y="))
+        .and(range())
+        .skip(literal(",z="))
+        .and(range())
+        .map(|(((x_min, x_max), (y_min, y_max)), (z_min, z_max))| {
+            Cuboid::new(Point([x_min, y_min, z_min]), Point([x_max, y_max, z_max]))
+        })
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum TargetState {
     On,
     Off,
 }
 
-impl FromStr for TargetState {
-    type Err = ();
+const STATES: &[(&str, TargetState)] = &[("on", TargetState::On), ("off", TargetState::Off)];
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "on" => Ok(Self::On),
-            "off" => Ok(Self::Off),
-            _ => Err(()),
-        }
-    }
-}
+pub type Input = Vec<CuboidInstruction>;
 
-fn parse_input(input: &str) -> impl Iterator<Item = CuboidInstruction> + '_ {
-    input.lines().map(|line| line.parse().unwrap())
+pub fn parse(input: &str) -> Input {
+    let instruction = one_of(STATES)
+        .skip(literal(" "))
+        .and(cuboid())
+        .map(|(state, cuboid)| CuboidInstruction { cuboid, state });
+    parser::parse_or_panic(lines(instruction), input)
 }
 
 pub fn perform(
@@ -161,9 +64,9 @@ pub fn perform(
             TargetState::Off => (&mut off, &mut on),
         };
 
-        *remove = std::mem::take(remove)
+        *remove = core::mem::take(remove)
             .into_iter()
-            .flat_map(|elem: Cuboid| elem.remove_overlap(&cuboid))
+            .flat_map(|elem: Cuboid| elem.difference(&cuboid))
             .collect();
         add.push(cuboid);
     }
@@ -174,7 +77,7 @@ pub fn perform(
             .fold(vec![], |list: Vec<Cuboid>, cuboid| {
                 let mut result: Vec<Cuboid> = list
                     .into_iter()
-                    .flat_map(|elem| elem.remove_overlap(&cuboid))
+                    .flat_map(|elem| elem.difference(&cuboid))
                     .collect();
                 result.push(cuboid);
                 result
@@ -184,47 +87,44 @@ pub fn perform(
     (compact_on, compact_off)
 }
 
-pub fn part1(input: &str) -> usize {
-    let (on, _off) = perform(parse_input(input));
+/// the region part 1 is limited to
+const INITIALIZATION_REGION: Cuboid = Cuboid {
+    min: Point([-50; 3]),
+    max: Point([50; 3]),
+};
 
-    let region = Cuboid {
-        x: -50..=50,
-        y: -50..=50,
-        z: -50..=50,
-    };
+pub fn part1(input: &Input) -> u64 {
+    let (on, _off) = perform(input.iter().cloned());
 
     on.into_iter()
-        .flat_map(|elem| elem.overlaps(&region))
-        .map(|elem| elem.size())
+        .flat_map(|elem| elem.intersection(&INITIALIZATION_REGION))
+        .map(|elem| elem.volume())
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-    let (on, _off) = perform(parse_input(input));
-
-    on.into_iter().map(|elem| elem.size()).sum()
-}
-
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day22.example1.txt"));
-    assert_eq!(part1(input), 590784);
-}
+pub fn part2(input: &Input) -> u64 {
+    let (on, _off) = perform(input.iter().cloned());
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day22.txt"));
-    assert_eq!(part1(input), 580012);
+    on.into_iter().map(|elem| elem.volume()).sum()
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day22.example2.txt");
-    assert_eq!(part2(input), 2758514936282235);
-}
+/// the disjoint cuboids that are on at the end, within the initialization region for part 1
+pub fn explain(input: &Input, part: u8) -> Value {
+    let (on, _off) = perform(input.iter().cloned());
+    let on = match part {
+        1 => on
+            .into_iter()
+            .flat_map(|elem| elem.intersection(&INITIALIZATION_REGION))
+            .collect(),
+        _ => on,
+    };
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day22.txt"));
-    assert_eq!(part2(input), 1334238660555542);
+    json::object([
+        ("count", on.len().into()),
+        (
+            "cubes_on",
+            on.iter().map(Cuboid::volume).sum::<u64>().into(),
+        ),
+        ("on", on.iter().map(to_json).collect::<Vec<_>>().into()),
+    ])
 }
@@ -1,8 +1,12 @@
+use crate::budget::{Budget, Exhausted};
 use crate::day23::Crab::*;
 use crate::day23::HallwaySpot::*;
 use crate::day23::Move::{FromRoom, ToRoom};
+use crate::parser::{self, array, literal, one_of, preceded, Parser};
+use crate::prelude::*;
+use crate::search::Search;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Input<const N: usize> {
     pub rooms: [[Crab; N]; 4],
 }
@@ -187,7 +191,7 @@ impl Move {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct State<const N: usize> {
     rooms: [[Option<Crab>; N]; 4],
     left: [Option<Crab>; 2],
@@ -217,7 +221,6 @@ impl<const N: usize> State<N> {
     /// ]}.into();
     /// assert!(state.is_final());
     ///```
-
     pub fn is_final(&self) -> bool {
         self.rooms.iter().enumerate().all(|(idx, room)| {
             room.iter().all(|elem| {
@@ -287,7 +290,7 @@ impl<const N: usize> From<Input<N>> for State<N> {
     }
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum Crab {
     Amber,
     Bronze,
@@ -315,33 +318,17 @@ impl Crab {
     }
 }
 
-fn parse_input(input: &str) -> Input<2> {
-    let mut lines = input.lines();
-    let head = lines.nth(2).unwrap().trim_matches('#');
-    let tail = lines.next().unwrap().trim().trim_matches('#');
-
-    let rooms = [head, tail].map(|elem| {
-        elem.splitn(4, '#')
-            .map(|elem| match elem {
-                "A" => Some(Amber),
-                "B" => Some(Bronze),
-                "C" => Some(Copper),
-                "D" => Some(Desert),
-                _ => None,
-            })
-            .collect::<Option<Vec<_>>>()
-    });
-
-    if let [Some(tops), Some(bottoms)] = rooms {
-        let rooms = tops
-            .into_iter()
-            .zip(bottoms.into_iter())
-            .map(|(a, b)| [a, b])
-            .collect::<Vec<_>>();
-        let rooms = rooms.try_into().unwrap();
-        Input { rooms }
-    } else {
-        panic!("{:?}", rooms)
+const CRABS: &[(&str, Crab)] = &[("A", Amber), ("B", Bronze), ("C", Copper), ("D", Desert)];
+
+pub fn parse(input: &str) -> Input<2> {
+    let row = || array::<_, _, 4>(one_of(CRABS), literal("#"));
+    let diagram = preceded(literal("#############\n#...........#\n###"), row())
+        .skip(literal("###\n  #"))
+        .and(row())
+        .skip(literal("#\n  #########"));
+    let (tops, bottoms) = parser::parse_or_panic(diagram, input);
+    Input {
+        rooms: [0, 1, 2, 3].map(|room| [tops[room].clone(), bottoms[room].clone()]),
     }
 }
 
@@ -354,9 +341,7 @@ pub fn possible_moves<const N: usize>(state: &State<N>) -> Vec<Move> {
         .flat_map(move |hallway_spot| {
             CRABS.into_iter().flat_map(move |crab| {
                 let hallway_spot = hallway_spot.clone();
-                (0..N)
-                    .into_iter()
-                    .map(move |room_idx| (RoomSpot(crab.clone(), room_idx), hallway_spot.clone()))
+                (0..N).map(move |room_idx| (RoomSpot(crab.clone(), room_idx), hallway_spot.clone()))
             })
         })
         .flat_map(|(room_spot, hallway_spot)| {
@@ -369,45 +354,38 @@ pub fn possible_moves<const N: usize>(state: &State<N>) -> Vec<Move> {
         .collect()
 }
 
-pub fn simulate<const N: usize>(
-    state: &State<N>,
-    depth: usize,
-    state_cost: usize,
-    minimum: &mut Option<usize>,
-) {
-    for movement in possible_moves(state) {
-        let mut new_state = state.clone();
-        let move_cost = new_state.apply_move(&movement);
-
-        let current_cost = move_cost + state_cost;
-
-        if minimum.map_or(false, |min| min < current_cost) {
-            continue;
-        }
+/// The least energy to get from `state` to the final state, Dijkstra over the states with the
+/// moves' energy as cost
+pub fn organize<const N: usize>(state: State<N>, budget: &Budget) -> Result<usize, Exhausted> {
+    let found = Search::new().within(budget).dijkstra(
+        state,
+        |state| {
+            possible_moves(state)
+                .into_iter()
+                .map(|movement| {
+                    let mut new_state = state.clone();
+                    let move_cost = new_state.apply_move(&movement);
+                    (new_state, move_cost)
+                })
+                .collect::<Vec<_>>()
+        },
+        State::is_final,
+    )?;
+    Ok(found.expect("the crabs can not be organized").cost)
+}
 
-        if new_state.is_final() {
-            let min = minimum.take();
-            *minimum = Some(min.map_or(current_cost, |cur| cur.min(current_cost)));
-            // no other moves can be more efficient than getting to the final state
-            break;
-        } else {
-            simulate(&new_state, depth + 1, current_cost, minimum);
-        }
-    }
+pub fn part1_within(input: &Input<2>, budget: &Budget) -> Result<usize, Exhausted> {
+    organize(State::from(input.clone()), budget)
 }
 
-pub fn part1(input: &str) -> usize {
-    let input = parse_input(input);
-    let init_state = State::from(input);
-    let mut min = None;
-    simulate(&init_state, 1, 0, &mut min);
-    min.unwrap()
+pub fn part1(input: &Input<2>) -> usize {
+    part1_within(input, &Budget::unlimited()).unwrap()
 }
 
-pub fn part2(input: &str) -> usize {
+pub fn part2_within(input: &Input<2>, budget: &Budget) -> Result<usize, Exhausted> {
     let Input {
         rooms: [[a_0, a_1], [b_0, b_1], [c_0, c_1], [d_0, d_1]],
-    } = parse_input(input);
+    } = input.clone();
 
     let input = Input::<4> {
         rooms: [
@@ -418,45 +396,17 @@ pub fn part2(input: &str) -> usize {
         ],
     };
 
-    let init_state = State::from(input);
-
-    let mut min = None;
-    simulate(&init_state, 1, 0, &mut min);
-    min.unwrap()
-}
-
-#[test]
-#[ignore]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day23.example.txt"));
-    assert_eq!(part1(input), 12521);
+    organize(State::from(input), budget)
 }
 
-#[test]
-#[ignore]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day23.txt"));
-    assert_eq!(part1(input), 18195);
+pub fn part2(input: &Input<2>) -> usize {
+    part2_within(input, &Budget::unlimited()).unwrap()
 }
 
+#[cfg(feature = "std")]
 #[test]
-#[ignore]
-fn part2_example() {
-    let input = include_str!("../input/day23.example.txt");
-    assert_eq!(part2(input), 44169);
-}
-
-#[test]
-#[ignore]
-fn part2_full() {
-    // #############
-    // #...........#
-    // ###A#C#B#A###
-    //   #D#C#B#A#
-    //   #D#B#A#C#
-    //   #D#D#B#C#
-    //   #########
-
-    let input = include_str!(concat!("../input/day23.txt"));
-    assert_eq!(part2(input), 50265);
+fn part1_example_times_out() {
+    let input = include_str!(concat!("../input/day23.example.txt"));
+    let budget = Budget::with_timeout(std::time::Duration::from_millis(10));
+    assert_eq!(part1_within(&parse(input), &budget), Err(Exhausted));
 }
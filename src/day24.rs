@@ -1,8 +1,10 @@
 use crate::day24::Arg2::Literal;
 use crate::day24::Operation::{Add, Div, Eq, Inp, Mod, Mul};
 use crate::day24::Register::*;
-use std::fmt::{Display, Formatter};
-use std::rc::{Rc, Weak};
+use crate::parser::{self, integer, lines, literal, one_of, preceded, Parser};
+use crate::prelude::*;
+use alloc::rc::{Rc, Weak};
+use core::fmt::{Display, Formatter};
 use RegisterState::{Input, Value};
 
 #[derive(Clone)]
@@ -42,7 +44,7 @@ pub enum RegisterState {
 }
 
 impl Display for RegisterState {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         match self {
             Value(x) => Display::fmt(x, f),
             RegisterState::Input(idx) => f.write_fmt(format_args!("input[{}]", idx)),
@@ -59,7 +61,7 @@ pub struct Expression {
 }
 
 impl Display for Expression {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let op = match &self.op {
             Inp => "inp",
             Add => "+",
@@ -90,55 +92,38 @@ pub enum Operation {
     Eq,
 }
 
-fn parse_register(reg: &str) -> Register {
-    match reg {
-        "w" => W,
-        "x" => X,
-        "y" => Y,
-        "z" => Z,
-        _ => panic!(),
-    }
-}
-
-fn parse_arg2(lit_or_reg: &str) -> Arg2 {
-    match lit_or_reg {
-        "w" => Arg2::Register(W),
-        "x" => Arg2::Register(X),
-        "y" => Arg2::Register(Y),
-        "z" => Arg2::Register(Z),
-        lit => Arg2::Literal(lit.parse().unwrap()),
-    }
-}
-
-pub fn parse_operator(op: &str) -> Operation {
-    match op {
-        "inp" => Inp,
-        "add" => Add,
-        "mul" => Mul,
-        "div" => Div,
-        "mod" => Mod,
-        "eql" => Eq,
-        _ => panic!(),
-    }
-}
-
-fn parse_input(input: &str) -> impl Iterator<Item = Instruction> + '_ {
-    input.lines().flat_map(|line| {
-        let parts = line.split(' ').collect::<Vec<_>>();
-        match parts.as_slice() {
-            ["inp", reg] => Some(Instruction {
+const REGISTERS: &[(&str, Register)] = &[("w", W), ("x", X), ("y", Y), ("z", Z)];
+
+const OPERATIONS: &[(&str, Operation)] = &[
+    ("inp", Inp),
+    ("add", Add),
+    ("mul", Mul),
+    ("div", Div),
+    ("mod", Mod),
+    ("eql", Eq),
+];
+
+pub type Instructions = Vec<Instruction>;
+
+pub fn parse(input: &str) -> Instructions {
+    let arg2 = one_of(REGISTERS)
+        .map(Arg2::Register)
+        .or(integer().map(Literal));
+    let instruction = one_of(OPERATIONS)
+        .skip(literal(" "))
+        .and(one_of(REGISTERS))
+        .and(preceded(literal(" "), arg2).optional())
+        .try_map(|((op, arg1), arg2)| match (op, arg2) {
+            (Inp, None) => Ok(Instruction {
                 op: Inp,
-                arg1: parse_register(reg),
+                arg1,
                 arg2: Literal(0),
             }),
-            [op, reg, arg2] => Some(Instruction {
-                op: parse_operator(op),
-                arg1: parse_register(reg),
-                arg2: parse_arg2(arg2),
-            }),
-            _ => None,
-        }
-    })
+            (Inp, Some(_)) => Err("`inp` with a single register"),
+            (op, Some(arg2)) => Ok(Instruction { op, arg1, arg2 }),
+            (_, None) => Err("an instruction with two arguments"),
+        });
+    parser::parse_or_panic(lines(instruction), input)
 }
 
 impl AluState {
@@ -269,15 +254,15 @@ impl AluState {
 }
 
 pub fn part1_instructions_to_code(input: &str) -> String {
-    let iter = parse_input(input);
+    let iter = parse(input);
     let mut alu = AluState::new();
-    iter.for_each(|inst| alu.apply_instruction(&inst));
+    iter.iter().for_each(|inst| alu.apply_instruction(inst));
 
     let AluState { shared, z, .. } = alu;
 
     let mut str = String::new();
 
-    use std::fmt::Write;
+    use core::fmt::Write;
 
     for (idx, expr) in shared.iter().enumerate() {
         if let Some(expr) = expr.upgrade() {
@@ -300,7 +285,7 @@ pub fn to_digits(serial: isize) -> [isize; 14] {
         .unwrap()
 }
 
-pub fn part1(_: &str) -> isize {
+pub fn part1(_: &Instructions) -> isize {
     let d0 = 1;
     let tmp = d0 + 12;
     for d1 in (1..3).rev() {
@@ -351,10 +336,6 @@ pub fn part1(_: &str) -> isize {
 
                             let result = digits.into_iter().fold(0, |acc, next| acc * 10 + next);
 
-                            // println!("{:?}", digits);
-                            assert_eq!(tmp, part1_fn(digits));
-                            assert_eq!(tmp, part1_fn2(digits));
-
                             if tmp == 0 {
                                 return result;
                             }
@@ -558,7 +539,67 @@ pub const fn part1_fn2(input: [isize; 14]) -> isize {
     tmp
 }
 
-pub fn part2(_: &str) -> isize {
+/// The serial numbers that meet the digit constraints of [`part1`], largest first or smallest first
+fn candidates(largest_first: bool) -> Vec<[isize; 14]> {
+    let digits = |range: core::ops::Range<isize>| -> Vec<isize> {
+        match largest_first {
+            true => range.rev().collect(),
+            false => range.collect(),
+        }
+    };
+    let d0 = 1;
+    let mut serials = vec![];
+    for d1 in digits(1..3) {
+        for d2 in digits(8..10) {
+            for d3 in digits(4..10) {
+                for d5 in digits(2..10) {
+                    for d6 in digits(3..10) {
+                        for d10 in digits(7..10) {
+                            serials.push([
+                                d0,
+                                d1,
+                                d2,
+                                d3,
+                                d3 - 3,
+                                d5,
+                                d6,
+                                d6 - 2,
+                                d5 - 1,
+                                d2 - 7,
+                                d10,
+                                d10 - 6,
+                                d1 + 7,
+                                d0 + 8,
+                            ]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    serials
+}
+
+/// the largest candidate that `monad` accepts
+fn largest_accepted(monad: fn([isize; 14]) -> isize) -> isize {
+    candidates(true)
+        .into_iter()
+        .find(|&digits| monad(digits) == 0)
+        .map(|digits| digits.into_iter().fold(0, |acc, next| acc * 10 + next))
+        .expect("Tried all serial numbers!")
+}
+
+/// [`part1`] checking the candidates with the expanded expressions of [`part1_fn`]
+pub fn part1_expanded(_: &Instructions) -> isize {
+    largest_accepted(part1_fn)
+}
+
+/// [`part1`] checking the candidates digit by digit with [`part1_fn2`]
+pub fn part1_stepwise(_: &Instructions) -> isize {
+    largest_accepted(part1_fn2)
+}
+
+pub fn part2(_: &Instructions) -> isize {
     let d0 = 1;
     let tmp = d0 + 12;
     for d1 in 1..3 {
@@ -609,10 +650,6 @@ pub fn part2(_: &str) -> isize {
 
                             let result = digits.into_iter().fold(0, |acc, next| acc * 10 + next);
 
-                            // println!("{:?}", digits);
-                            assert_eq!(tmp, part1_fn(digits));
-                            assert_eq!(tmp, part1_fn2(digits));
-
                             if tmp == 0 {
                                 return result;
                             }
@@ -624,21 +661,3 @@ pub fn part2(_: &str) -> isize {
     }
     panic!("Tried all serial numbers!")
 }
-
-#[test]
-fn equivalent() {
-    let serial = 13579246899999;
-    let digits = to_digits(serial);
-
-    assert_eq!(part1_fn(digits), part1_fn2(digits))
-}
-
-#[test]
-fn part1_full() {
-    assert_eq!(part1(""), 12996997829399);
-}
-
-#[test]
-fn part2_full() {
-    assert_eq!(part2(""), 11841231117189);
-}
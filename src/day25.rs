@@ -1,117 +1,156 @@
 use crate::day25::Spot::{Down, Empty, Right};
+use crate::grid::{Grid, Pos};
+use crate::prelude::*;
+use crate::repl::{arg, Command, Repl, Session};
 
-enum Spot {
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Spot {
     Down,
     Right,
     Empty,
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Spot>> {
-    input
-        .lines()
-        .map(|line| {
-            line.chars()
-                .map(|char| match char {
-                    'v' => Down,
-                    '>' => Right,
-                    '.' => Empty,
-                    _ => panic!(),
-                })
-                .collect()
-        })
-        .collect()
+impl core::fmt::Display for Spot {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let char = match self {
+            Down => 'v',
+            Right => '>',
+            Empty => '.',
+        };
+        write!(f, "{}", char)
+    }
 }
 
-pub fn part1(input: &str) -> u32 {
-    let mut game_board = parse_input(input);
+/// The seafloor wraps around at its edges
+pub type Input = Grid<Spot>;
 
-    let mut empty = vec![];
+pub fn parse(input: &str) -> Input {
+    Grid::parse(input, |char| match char {
+        'v' => Down,
+        '>' => Right,
+        '.' => Empty,
+        _ => panic!(),
+    })
+    .wrapping()
+}
 
-    for (row_idx, row) in game_board.iter().enumerate() {
-        for (column_idx, entry) in row.iter().enumerate() {
-            match entry {
-                Empty => {
-                    empty.push((row_idx, column_idx));
-                }
-                Right | Down => {}
-            }
-        }
+/// the first step on which no sea cucumber moves
+pub fn part1(input: &Input) -> u32 {
+    let mut herds = Herds::new(input.clone());
+    let mut steps = 1;
+    while herds.step() {
+        steps += 1;
     }
+    steps
+}
 
-    let mut empty_down = vec![];
-    let mut empty_right = empty;
-    let mut not_moved;
-    let mut iterations = 0;
+pub fn part2(_input: &Input) -> u32 {
+    // There is no part 2!
+    0
+}
 
-    loop {
-        not_moved = true;
+/// The seafloor with the spots that are empty since the last step, only those can take a sea
+/// cucumber so a step doesn't look at the rest of the board
+#[derive(Clone)]
+struct Herds {
+    board: Input,
+    empty: Vec<Pos>,
+}
 
-        empty_right
-            .drain(..)
-            .flat_map(|(row, column)| {
-                let origin = (
-                    row,
-                    (column + game_board[row].len() - 1) % game_board[row].len(),
-                );
-                if matches! { game_board[origin.0][origin.1], Right } {
-                    empty_down.push(origin);
-                    not_moved = false;
-                    Some((origin, (row, column)))
-                } else {
-                    empty_down.push((row, column));
-                    None
-                }
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(origin, (row, column))| {
-                game_board[origin.0][origin.1] = Empty;
-                game_board[row][column] = Right;
-            });
+impl Herds {
+    fn new(board: Input) -> Self {
+        let empty = board
+            .iter()
+            .filter(|&(_, &spot)| spot == Empty)
+            .map(|(pos, _)| pos)
+            .collect();
+        Herds { board, empty }
+    }
 
-        empty_down
-            .drain(..)
-            .flat_map(|(row, column)| {
-                let origin = ((row + game_board.len() - 1) % game_board.len(), column);
-                if matches! { game_board[origin.0][origin.1], Down } {
-                    empty_right.push(origin);
-                    not_moved = false;
-                    Some((origin, (row, column)))
+    /// moves the right herd then the down herd once, returns whether any sea cucumber moved
+    fn step(&mut self) -> bool {
+        let mut moved = false;
+        for (herd, from) in [(Right, (0, -1)), (Down, (-1, 0))] {
+            let mut empty = Vec::with_capacity(self.empty.len());
+            let mut moves = vec![];
+            for &pos in &self.empty {
+                let origin = self.board.offset(pos, from).unwrap();
+                if self.board[origin] == herd {
+                    moves.push((origin, pos));
+                    empty.push(origin);
                 } else {
-                    empty_right.push((row, column));
-                    None
+                    empty.push(pos);
                 }
-            })
-            .collect::<Vec<_>>()
-            .into_iter()
-            .for_each(|(origin, (row, column))| {
-                game_board[origin.0][origin.1] = Empty;
-                game_board[row][column] = Down;
-            });
-
-        iterations += 1;
-
-        if not_moved {
-            break;
+            }
+            for &(origin, pos) in &moves {
+                self.board[origin] = Empty;
+                self.board[pos] = herd;
+            }
+            moved |= !moves.is_empty();
+            self.empty = empty;
         }
+        moved
     }
-
-    iterations
 }
 
-pub fn part2(_input: &str) -> u32 {
-    println!("There is no part 2!");
-    0
+/// the sea cucumbers once they stop moving
+pub fn visualize(input: &Input) -> String {
+    let mut herds = Herds::new(input.clone());
+    while herds.step() {}
+    herds.board.to_string()
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day25.example.txt"));
-    assert_eq!(part1(input), 58);
+struct Seafloor {
+    initial: Herds,
+    herds: Herds,
+    steps: usize,
 }
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day25.txt"));
-    assert_eq!(part1(input), 509);
+const COMMANDS: &[Command<Seafloor>] = &[
+    Command {
+        name: "step",
+        args: "[count]",
+        help: "do the next steps, one by default, and show the seafloor",
+        run: |seafloor, args| {
+            let count: usize = arg(args, 0, 1)?;
+            let mut moved = false;
+            for _ in 0..count {
+                moved |= seafloor.herds.step();
+            }
+            seafloor.steps += count;
+            let status = if moved { "moved" } else { "nothing moved" };
+            Ok(format!(
+                "step {}: {}\n{}",
+                seafloor.steps, status, seafloor.herds.board
+            ))
+        },
+    },
+    Command {
+        name: "show",
+        args: "",
+        help: "show the seafloor",
+        run: |seafloor, _| Ok(seafloor.herds.board.to_string()),
+    },
+    Command {
+        name: "reset",
+        args: "",
+        help: "go back to the start",
+        run: |seafloor, _| {
+            seafloor.herds = seafloor.initial.clone();
+            seafloor.steps = 0;
+            Ok(seafloor.herds.board.to_string())
+        },
+    },
+];
+
+pub fn repl(input: Input) -> Box<dyn Session> {
+    let initial = Herds::new(input);
+    Box::new(Repl::new(
+        Seafloor {
+            herds: initial.clone(),
+            initial,
+            steps: 0,
+        },
+        COMMANDS,
+    ))
 }
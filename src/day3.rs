@@ -1,7 +1,12 @@
-use std::cmp::Ordering;
+use crate::parser::{self, lines, take_while1, Parser};
+use crate::prelude::*;
+use core::cmp::Ordering;
 
-fn parse_input(input: &str) -> impl Iterator<Item = &[u8]> + '_ {
-    input.lines().map(|str| str.as_bytes())
+pub type Input = Vec<Vec<u8>>;
+
+pub fn parse(input: &str) -> Input {
+    let bits = take_while1("a binary digit", |c| c == '0' || c == '1');
+    parser::parse_or_panic(lines(bits.map(|bits| bits.as_bytes().to_vec())), input)
 }
 
 /// Produces a bit mask with the lower n bits set
@@ -13,8 +18,8 @@ pub const fn mask(bits: usize) -> u32 {
     (1 << bits) - 1
 }
 
-pub fn part1(input: &str) -> u32 {
-    let mut iter = parse_input(input).peekable();
+pub fn part1(input: &Input) -> u32 {
+    let mut iter = input.iter().peekable();
 
     let bit_count = iter.peek().unwrap().len();
     let mut bit_vector = vec![0; bit_count];
@@ -40,8 +45,8 @@ pub fn part1(input: &str) -> u32 {
     gamma * epsilon
 }
 
-pub fn part2(input: &str) -> u32 {
-    let mut input_list: Vec<_> = parse_input(input).collect();
+pub fn part2(input: &Input) -> u32 {
+    let mut input_list: Vec<_> = input.iter().map(Vec::as_slice).collect();
 
     fn reduce_list(list: &mut Vec<&[u8]>, most: bool) -> u32 {
         let mut idx = 0;
@@ -90,27 +95,3 @@ pub fn part2(input: &str) -> u32 {
 
     oxygen_generator * co2_scrubber
 }
-
-#[test]
-fn part1_example() {
-    let input = include_str!("../input/day3.example.txt");
-    assert_eq!(part1(input), 22 * 9);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day3.txt"));
-    assert_eq!(part1(input), 3242606);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day3.example.txt");
-    assert_eq!(part2(input), 23 * 10);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day3.txt"));
-    assert_eq!(part2(input), 4856080);
-}
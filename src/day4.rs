@@ -1,63 +1,30 @@
-struct BoardCollector<I>(I);
-
-impl<'a, I> Iterator for BoardCollector<I>
-where
-    I: Iterator<Item = &'a str>,
-{
-    type Item = [[u32; 5]; 5];
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()?;
-        Some([(); 5].map(|_| {
-            let mut line = self
-                .0
-                .next()
-                .unwrap()
-                .split(' ')
-                .filter(|elem| !elem.is_empty())
-                .map(|elem| elem.parse().unwrap());
-            [(); 5].map(|_| line.next().unwrap())
-        }))
-    }
+use crate::json::{self, Value};
+use crate::parser::{self, array, blocks, integer, literal, preceded, separated, spaces, Parser};
+use crate::prelude::*;
+use crate::rng::Rng;
+
+pub struct Input {
+    balls: Vec<u32>,
+    boards: Vec<[[u32; 5]; 5]>,
 }
 
-fn parse_input(input: &str) -> (Vec<u32>, impl Iterator<Item = [[u32; 5]; 5]> + '_) {
-    let mut lines = input.lines();
-    let balls = lines
-        .next()
-        .unwrap()
-        .split(',')
-        .map(|elem| elem.parse())
-        .collect::<Result<_, _>>()
-        .unwrap();
-
-    (balls, BoardCollector(lines))
+pub fn parse(input: &str) -> Input {
+    let balls = separated(integer(), literal(","));
+    // numbers are right-aligned, single digits have an extra space in front
+    let row = preceded(spaces().optional(), array(integer(), spaces()));
+    let board = array(row, literal("\n"));
+    let (balls, boards) =
+        parser::parse_or_panic(balls.skip(literal("\n\n")).and(blocks(board)), input);
+    Input { balls, boards }
 }
 
 pub fn process_board(numbers: &[u32], board: [[u32; 5]; 5]) -> (usize, u32) {
-    let mut min = usize::MAX;
-
-    for x in 0..5 {
-        let mut max_row = usize::MIN;
-        let mut max_column = usize::MIN;
-
-        for y in 0..5 {
-            max_column = max_column.max(
-                numbers
-                    .iter()
-                    .position(|&elem| elem == board[y][x])
-                    .unwrap(),
-            );
-            max_row = max_row.max(
-                numbers
-                    .iter()
-                    .position(|&elem| elem == board[x][y])
-                    .unwrap(),
-            )
-        }
-
-        min = min.min(max_row.min(max_column));
-    }
+    let drawn = |number| numbers.iter().position(|&elem| elem == number).unwrap();
+    let rows = board
+        .iter()
+        .map(|row| row.iter().map(|&number| drawn(number)).max().unwrap());
+    let columns = (0..5).map(|x| board.iter().map(|row| drawn(row[x])).max().unwrap());
+    let min = rows.chain(columns).min().unwrap();
 
     let balls = &numbers[..=min];
 
@@ -74,53 +41,99 @@ pub enum DesiredResult {
     Loose,
 }
 
-pub fn both(input: &str, want: DesiredResult) -> u32 {
-    let (balls, mut boards) = parse_input(input);
+/// the index of the first board to win, or the last one, with its round and unmarked sum
+fn select_board(input: &Input, want: DesiredResult) -> (usize, usize, u32) {
+    let Input { balls, boards } = input;
+    let mut boards = boards.iter().enumerate();
 
-    let (mut rounds, mut remaining_score) = process_board(&balls, boards.next().unwrap());
+    let (first, board) = boards.next().unwrap();
+    let (mut rounds, mut remaining_score) = process_board(balls, *board);
+    let mut selected = first;
 
-    for board in boards {
-        let (new_rounds, new_remaining_score) = process_board(&balls, board);
+    for (idx, board) in boards {
+        let (new_rounds, new_remaining_score) = process_board(balls, *board);
         if match want {
             DesiredResult::Win => new_rounds < rounds,
             DesiredResult::Loose => new_rounds > rounds,
         } {
             rounds = new_rounds;
             remaining_score = new_remaining_score;
+            selected = idx;
         }
     }
 
-    balls[rounds] * remaining_score
+    (selected, rounds, remaining_score)
 }
 
-pub fn part1(input: &str) -> u32 {
-    both(input, DesiredResult::Win)
+pub fn both(input: &Input, want: DesiredResult) -> u32 {
+    let (_, rounds, remaining_score) = select_board(input, want);
+    input.balls[rounds] * remaining_score
 }
 
-pub fn part2(input: &str) -> u32 {
-    both(input, DesiredResult::Loose)
+pub fn part1(input: &Input) -> u32 {
+    both(input, DesiredResult::Win)
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!("../input/day4.example.txt");
-    assert_eq!(part1(input), 188 * 24);
+pub fn part2(input: &Input) -> u32 {
+    both(input, DesiredResult::Loose)
 }
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day4.txt"));
-    assert_eq!(part1(input), 6592);
-}
+/// `size` boards, the balls are every number on them, at least 100, in random order
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let boards = size.max(1);
+    let numbers = (boards * 25).max(100) as u64;
+    let mut balls = (0..numbers).collect::<Vec<_>>();
+    rng.shuffle(&mut balls);
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day4.example.txt");
-    assert_eq!(part2(input), 148 * 13);
+    let mut input = balls
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    for _ in 0..boards {
+        let mut board = Vec::with_capacity(25);
+        while board.len() < 25 {
+            let number = rng.range(0..numbers);
+            if !board.contains(&number) {
+                board.push(number);
+            }
+        }
+        input += "\n";
+        for row in board.chunks(5) {
+            input += "\n";
+            input += &row
+                .iter()
+                .map(|number| format!("{:>2}", number))
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+    }
+    input
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day4.txt"));
-    assert_eq!(part2(input), 31755);
+/// the winning board of part 1 or the last board to win of part 2, with the round it wins in
+pub fn explain(input: &Input, part: u8) -> Value {
+    let want = match part {
+        1 => DesiredResult::Win,
+        _ => DesiredResult::Loose,
+    };
+    let (idx, rounds, unmarked_sum) = select_board(input, want);
+    let drawn = &input.balls[..=rounds];
+    let board = input.boards[idx];
+
+    json::object([
+        ("board", idx.into()),
+        ("round", (rounds + 1).into()),
+        ("last_ball", input.balls[rounds].into()),
+        ("rows", board.map(Value::from).into()),
+        (
+            "marked",
+            board
+                .map(|row| row.map(|number| drawn.contains(&number)))
+                .map(Value::from)
+                .into(),
+        ),
+        ("unmarked_sum", unmarked_sum.into()),
+        ("score", (input.balls[rounds] * unmarked_sum).into()),
+    ])
 }
@@ -1,16 +1,19 @@
-use std::collections::VecDeque;
+use crate::parser::{self, integer, literal, separated};
+use crate::prelude::*;
+use crate::rng::Rng;
+use alloc::collections::VecDeque;
 
-fn parse_input(input: &str) -> impl Iterator<Item = usize> + '_ {
-    input
-        .lines()
-        .flat_map(|line| line.split(',').map(|elem| elem.parse().unwrap()))
+pub type Input = Vec<usize>;
+
+pub fn parse(input: &str) -> Input {
+    parser::parse_or_panic(separated(integer(), literal(",")), input)
 }
 
-pub fn both(input: &str, days: u32) -> usize {
+pub fn both(input: &[usize], days: u32) -> usize {
     let mut aging_queue = VecDeque::with_capacity(9);
     aging_queue.resize(9, 0);
     let mut counter = 0;
-    for elem in parse_input(input) {
+    for &elem in input {
         counter += 1;
         aging_queue[elem] += 1;
     }
@@ -24,34 +27,41 @@ pub fn both(input: &str, days: u32) -> usize {
     counter
 }
 
-pub fn part1(input: &str) -> usize {
-    both(input, 80)
-}
+/// simulates every fish on its own, the population grows exponentially so this only works for part 1
+pub fn both_naive(input: &[usize], days: u32) -> usize {
+    let mut fish = input.to_vec();
 
-pub fn part2(input: &str) -> usize {
-    both(input, 256)
+    for _day in 1..=days {
+        let mut new_fish = 0;
+        for timer in fish.iter_mut() {
+            if *timer == 0 {
+                *timer = 6;
+                new_fish += 1;
+            } else {
+                *timer -= 1;
+            }
+        }
+        fish.resize(fish.len() + new_fish, 8);
+    }
+    fish.len()
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!("../input/day6.example.txt");
-    assert_eq!(part1(input), 5934);
+pub fn part1(input: &Input) -> usize {
+    both(input, 80)
 }
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day6.txt"));
-    assert_eq!(part1(input), 349549);
+pub fn part2(input: &Input) -> usize {
+    both(input, 256)
 }
 
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day6.example.txt");
-    assert_eq!(part2(input), 26984457539);
+pub fn part1_naive(input: &Input) -> usize {
+    both_naive(input, 80)
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day6.txt"));
-    assert_eq!(part2(input), 1589590444365);
+/// `size` fish with their initial timers
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let timers = (0..size.max(1))
+        .map(|_| rng.range(1..6).to_string())
+        .collect::<Vec<_>>();
+    timers.join(",") + "\n"
 }
@@ -1,26 +1,32 @@
-fn parse_input(input: &str) -> impl Iterator<Item = i32> + Clone + '_ {
-    input.split(',').map(|elem| elem.parse().unwrap())
+use crate::parser::{self, integer, literal, separated};
+use crate::prelude::*;
+use crate::rng::Rng;
+
+pub type Input = Vec<i32>;
+
+pub fn parse(input: &str) -> Input {
+    parser::parse_or_panic(separated(integer(), literal(",")), input)
 }
 
-pub fn part1(input: &str) -> i32 {
-    let mut iter: Vec<_> = parse_input(input).collect();
+pub fn part1(input: &Input) -> i32 {
+    let mut iter = input.clone();
     iter.sort_unstable();
     let median = iter[iter.len() / 2];
 
     iter.iter().map(|elem| (elem - median).abs()).sum()
 }
 
-pub fn part2(input: &str) -> i32 {
-    let mut iter: Vec<_> = parse_input(input).collect();
+fn cost(dist: i32) -> i32 {
+    dist * (dist + 1) / 2
+}
+
+pub fn part2(input: &Input) -> i32 {
+    let mut iter = input.clone();
     iter.sort_unstable();
 
     let min = iter.first().unwrap();
     let max = iter.last().unwrap();
 
-    fn cost(dist: i32) -> i32 {
-        dist * (dist + 1) / 2
-    }
-
     // TODO can we do better than enumerating all values between min and max?
 
     (*min..=*max)
@@ -29,26 +35,26 @@ pub fn part2(input: &str) -> i32 {
         .unwrap()
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!("../input/day7.example.txt");
-    assert_eq!(part1(input), 37);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day7.txt"));
-    assert_eq!(part1(input), 348996);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day7.example.txt");
-    assert_eq!(part2(input), 168);
+/// The real valued optimum is within 1/2 of the mean position,
+/// so only the integers next to the mean need to be checked.
+pub fn part2_mean(positions: &Input) -> i32 {
+    let mean = positions.iter().sum::<i32>() / positions.len() as i32;
+
+    (mean - 1..=mean + 1)
+        .map(|dest| {
+            positions
+                .iter()
+                .map(|start| cost((start - dest).abs()))
+                .sum()
+        })
+        .min()
+        .unwrap()
 }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day7.txt"));
-    assert_eq!(part2(input), 98231647);
+/// `size` crab positions, without a trailing newline like the real input
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let positions = (0..size.max(1))
+        .map(|_| rng.range(0..2000).to_string())
+        .collect::<Vec<_>>();
+    positions.join(",")
 }
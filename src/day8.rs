@@ -1,7 +1,12 @@
+use crate::parser::{
+    self, array, lines, literal, preceded, separated, spaces, take_while1, Parser,
+};
+use crate::prelude::*;
+
 type Segments = u8;
 
 #[derive(Debug)]
-struct Sequence {
+pub struct Sequence {
     examples: Vec<Segments>,
     output: [Segments; 4],
 }
@@ -80,27 +85,22 @@ pub fn str_to_segment(input: &str) -> u8 {
     result
 }
 
-fn parse_input(input: &str) -> impl Iterator<Item = Sequence> + '_ {
-    input.lines().map(|line| {
-        let (examples, outputs) = line.split_once('|').unwrap();
-        let examples = examples
-            .split(' ')
-            .map(str::trim)
-            .map(str_to_segment)
-            .collect();
-        let outputs = outputs
-            .split(' ')
-            .map(str::trim)
-            .filter(|elme| !elme.is_empty())
-            .map(str_to_segment)
-            .collect::<Vec<_>>();
-        let output = [outputs[0], outputs[1], outputs[2], outputs[3]];
-        Sequence { examples, output }
-    })
+pub type Input = Vec<Sequence>;
+
+pub fn parse(input: &str) -> Input {
+    let digit =
+        || take_while1("a segment from a to g", |c| ('a'..='g').contains(&c)).map(str_to_segment);
+    // some inputs have runs of spaces around the `|`
+    let sequence = separated(digit(), spaces())
+        .skip(preceded(spaces(), literal("|")).skip(spaces()))
+        .and(array(digit(), spaces()))
+        .map(|(examples, output)| Sequence { examples, output });
+    parser::parse_or_panic(lines(sequence), input)
 }
 
-pub fn part1(input: &str) -> usize {
-    parse_input(input)
+pub fn part1(input: &Input) -> usize {
+    input
+        .iter()
         .map(|seq| {
             let res = seq
                 .output
@@ -112,42 +112,6 @@ pub fn part1(input: &str) -> usize {
         .sum()
 }
 
-pub fn part2(input: &str) -> usize {
-    parse_input(input).map(|sequence| sequence.decode()).sum()
-}
-
-#[test]
-fn part1_example1() {
-    let input = include_str!("../input/day8.example1.txt");
-    assert_eq!(part1(input), 0);
-}
-
-#[test]
-fn part1_example2() {
-    let input = include_str!("../input/day8.example2.txt");
-    assert_eq!(part1(input), 26);
-}
-
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day8.txt"));
-    assert_eq!(part1(input), 397);
-}
-
-#[test]
-fn part2_example1() {
-    let input = include_str!("../input/day8.example1.txt");
-    assert_eq!(part2(input), 5353);
-}
-
-#[test]
-fn part2_example2() {
-    let input = include_str!("../input/day8.example2.txt");
-    assert_eq!(part2(input), 61229);
-}
-
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day8.txt"));
-    assert_eq!(part2(input), 1027422);
+pub fn part2(input: &Input) -> usize {
+    input.iter().map(|sequence| sequence.decode()).sum()
 }
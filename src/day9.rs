@@ -1,100 +1,84 @@
-#![allow(clippy::ptr_arg)]
+use crate::grid::{Grid, Pos};
+use crate::json::{self, Value};
+use crate::prelude::*;
+use crate::search;
 
-use std::collections::{HashSet, VecDeque};
+pub type Input = Grid<u8>;
 
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|line| line.chars().map(|char| char as u8 - b'0').collect())
-        .collect()
+pub fn parse(input: &str) -> Input {
+    Grid::parse(input, |char| char as u8 - b'0')
 }
 
-pub fn neighbours<T: Copy>(
-    x: usize,
-    y: usize,
-    map: &Vec<Vec<T>>,
-) -> impl Iterator<Item = (usize, usize, T)> {
-    let a = x.checked_sub(1).and_then(|x| {
-        map.get(x)
-            .map(|elem| (x, elem))
-            .and_then(|(x, row)| row.get(y).map(|&elem| (x, y, elem)))
-    });
-    let b = x.checked_add(1).and_then(|x| {
-        map.get(x)
-            .map(|elem| (x, elem))
-            .and_then(|(x, row)| row.get(y).map(|&elem| (x, y, elem)))
-    });
-    let c = y
-        .checked_sub(1)
-        .zip(map.get(x))
-        .and_then(|(y, row)| row.get(y).map(|&elem| (x, y, elem)));
-    let d = y
-        .checked_add(1)
-        .zip(map.get(x))
-        .and_then(|(y, row)| row.get(y).map(|&elem| (x, y, elem)));
-    [a, b, c, d].into_iter().flatten()
+pub fn low(map: &Grid<u8>) -> impl Iterator<Item = (Pos, u8)> + '_ {
+    map.iter()
+        .map(|(pos, &value)| (pos, value))
+        .filter(|&(pos, value)| map.neighbours4(pos).all(|n| map[n] > value))
 }
 
-pub fn positions<T: Copy>(map: &Vec<Vec<T>>) -> impl Iterator<Item = (usize, usize, T)> + '_ {
-    map.iter().enumerate().flat_map(|(x_idx, row)| {
-        row.iter()
-            .copied()
-            .enumerate()
-            .map(move |(y_idx, elem)| (x_idx, y_idx, elem))
+pub fn basin_size(pos: Pos, map: &Grid<u8>) -> usize {
+    search::reachable(pos, |&pos| {
+        map.neighbours4(pos)
+            .filter(move |&n| map[n] > map[pos] && map[n] != 9)
     })
+    .len()
 }
 
-pub fn low(map: &Vec<Vec<u8>>) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
-    positions(map).filter(|&(x, y, value)| neighbours(x, y, map).all(|(_, _, n)| n > value))
+pub fn part1(depth_map: &Input) -> u32 {
+    low(depth_map).map(|(_, v)| v as u32 + 1).sum()
 }
 
-pub fn basin_size(x: usize, y: usize, depth: u8, map: &Vec<Vec<u8>>) -> usize {
-    let mut todo = VecDeque::from([(x, y, depth)]);
-    let mut processed = HashSet::new();
-    while let Some(pos) = todo.pop_front() {
-        if processed.insert((pos.0, pos.1)) {
-            todo.extend(neighbours(pos.0, pos.1, map).filter(|&(_, _, d)| d > pos.2 && d != 9))
-        }
-    }
-    processed.len()
-}
-
-pub fn part1(input: &str) -> u32 {
-    let depth_map = parse_input(input);
-    low(&depth_map).map(|(_, _, v)| v as u32 + 1).sum()
-}
-
-pub fn part2(input: &str) -> usize {
-    let depth_map = parse_input(input);
-
-    let mut basins = low(&depth_map)
-        .map(|(x, y, value)| basin_size(x, y, value, &depth_map))
+pub fn part2(depth_map: &Input) -> usize {
+    let mut basins = low(depth_map)
+        .map(|(pos, _)| basin_size(pos, depth_map))
         .collect::<Vec<_>>();
     basins.sort_unstable();
     basins.reverse();
     basins.iter().take(3).product()
 }
 
-#[test]
-fn part1_example() {
-    let input = include_str!(concat!("../input/day9.example.txt"));
-    assert_eq!(part1(input), 2 + 1 + 6 + 6);
-}
+/// the low points of part 1, or the basins of part 2 from largest to smallest
+pub fn explain(depth_map: &Input, part: u8) -> Value {
+    let point = |(row, column): Pos, height: u8| {
+        json::object([
+            ("row", row.into()),
+            ("column", column.into()),
+            ("height", height.into()),
+        ])
+    };
 
-#[test]
-fn part1_full() {
-    let input = include_str!(concat!("../input/day9.txt"));
-    assert_eq!(part1(input), 480);
-}
-
-#[test]
-fn part2_example() {
-    let input = include_str!("../input/day9.example.txt");
-    assert_eq!(part2(input), 9 * 14 * 9);
-}
+    if part == 1 {
+        let low_points = low(depth_map)
+            .map(|(pos, height)| point(pos, height))
+            .collect::<Vec<_>>();
+        return json::object([
+            ("count", low_points.len().into()),
+            ("low_points", low_points.into()),
+            ("risk_level_sum", part1(depth_map).into()),
+        ]);
+    }
 
-#[test]
-fn part2_full() {
-    let input = include_str!(concat!("../input/day9.txt"));
-    assert_eq!(part2(input), 1045660);
+    let mut basins = low(depth_map)
+        .map(|(pos, height)| (basin_size(pos, depth_map), point(pos, height)))
+        .collect::<Vec<_>>();
+    basins.sort_by(|(a, _), (b, _)| b.cmp(a));
+    let largest = basins
+        .iter()
+        .take(3)
+        .map(|&(size, _)| size)
+        .collect::<Vec<_>>();
+    json::object([
+        ("count", basins.len().into()),
+        ("largest", largest.into()),
+        (
+            "basins",
+            basins
+                .into_iter()
+                .map(|(size, low_point)| {
+                    json::object([("size", size.into()), ("low_point", low_point)])
+                })
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        ("product", part2(depth_map).into()),
+    ])
 }
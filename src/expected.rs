@@ -0,0 +1,113 @@
+//! The `input/dayN*.expected` sidecars listing the expected answers for an input file
+//!
+//! ```text
+//! part1: 7
+//! part2: 5
+//! ```
+//!
+//! Multi-line answers start on the line after an empty `partN:` and run until the next key.
+//! An `ignore: reason` line marks the input as too slow to check routinely.
+//!
+//! A profile's answers file holds the sidecars of all days, each after a `[dayN]` line.
+//!
+//! Only uses `alloc` so that `build.rs` can include this file as well.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expected {
+    pub ignore: Option<String>,
+    /// the answer per part number, multi-line answers end with a newline
+    pub answers: BTreeMap<u8, String>,
+}
+
+/// `Some((key, value))` for `partN: value` and `ignore: reason` lines
+fn key(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let is_part = key
+        .strip_prefix("part")
+        .is_some_and(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    (is_part || key == "ignore").then(|| (key, value.trim()))
+}
+
+pub fn parse(content: &str) -> Result<Expected, String> {
+    let mut expected = Expected::default();
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        match key(line) {
+            Some(("ignore", reason)) => expected.ignore = Some(reason.to_string()),
+            Some((part, value)) => {
+                let part = part["part".len()..]
+                    .parse()
+                    .map_err(|_| format!("Invalid part: {:?}", part))?;
+                let answer = if value.is_empty() {
+                    let mut answer = String::new();
+                    while let Some(line) = lines.next_if(|line| key(line).is_none()) {
+                        answer += line;
+                        answer += "\n";
+                    }
+                    answer
+                } else {
+                    value.to_string()
+                };
+                expected.answers.insert(part, answer);
+            }
+            None if line.trim().is_empty() => {}
+            None => return Err(format!("Unexpected line in expected file: {:?}", line)),
+        }
+    }
+
+    Ok(expected)
+}
+
+/// the expected answers per day of a profile's answers file
+pub fn parse_sections(content: &str) -> Result<BTreeMap<u8, Expected>, String> {
+    let mut sections = BTreeMap::new();
+    let mut current: Option<(u8, String)> = None;
+
+    for line in content.lines() {
+        let header = line
+            .trim()
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'));
+        if let Some(header) = header {
+            let day = header
+                .strip_prefix("day")
+                .and_then(|day| day.parse().ok())
+                .ok_or_else(|| format!("Invalid section: {:?}", line))?;
+            if let Some((day, section)) = current.replace((day, String::new())) {
+                sections.insert(day, parse(&section)?);
+            }
+        } else if let Some((_, section)) = &mut current {
+            *section += line;
+            *section += "\n";
+        } else if !line.trim().is_empty() {
+            return Err(format!("Line outside of a [dayN] section: {:?}", line));
+        }
+    }
+    if let Some((day, section)) = current {
+        sections.insert(day, parse(&section)?);
+    }
+
+    Ok(sections)
+}
+
+#[test]
+fn parse_sidecar() {
+    let expected = parse("ignore: slow\npart1: 7\npart2:\n#.\n.#\n").unwrap();
+    assert_eq!(expected.ignore.as_deref(), Some("slow"));
+    assert_eq!(expected.answers[&1], "7");
+    assert_eq!(expected.answers[&2], "#.\n.#\n");
+    assert!(parse("part1 7").is_err());
+}
+
+#[test]
+fn parse_answers_file() {
+    let sections = parse_sections("[day1]\npart1: 7\n\n[day13]\npart2:\n#.\n").unwrap();
+    assert_eq!(sections[&1].answers[&1], "7");
+    assert_eq!(sections[&13].answers[&2], "#.\n");
+    assert!(parse_sections("part1: 7").is_err());
+}
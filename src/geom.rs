@@ -0,0 +1,447 @@
+//! Integer geometry: points, segments, axis-aligned boxes and the rotations of a cube
+//!
+//! Coordinates are `i64` in any number of dimensions. Boxes include both corners, like the
+//! puzzles' `x=10..12` ranges.
+
+use crate::parser::{array, integer, literal, Parser};
+use crate::prelude::*;
+use core::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, RangeInclusive, Sub, SubAssign};
+use core::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Point<N> {
+    pub const ORIGIN: Self = Point([0; N]);
+
+    pub fn x(&self) -> i64 {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> i64 {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> i64 {
+        self.0[2]
+    }
+
+    /// -1, 0 or 1 per coordinate
+    pub fn signum(self) -> Self {
+        Point(self.0.map(i64::signum))
+    }
+
+    pub fn manhattan(self, other: Self) -> i64 {
+        (self - other).0.iter().map(|coord| coord.abs()).sum()
+    }
+
+    /// The largest difference in any coordinate
+    pub fn chebyshev(self, other: Self) -> i64 {
+        (self - other)
+            .0
+            .iter()
+            .map(|coord| coord.abs())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &i64 {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut i64 {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, other: Self) {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, other: Self) {
+        self.0.iter_mut().zip(other.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point(self.0.map(|coord| -coord))
+    }
+}
+
+impl<const N: usize> Mul<i64> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, factor: i64) -> Self {
+        Point(self.0.map(|coord| coord * factor))
+    }
+}
+
+/// `x,y,z`, whitespace around the coordinates is ignored
+impl<const N: usize> FromStr for Point<N> {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let coords = s
+            .split(',')
+            .map(|coord| coord.trim().parse().map_err(|_| ()))
+            .collect::<Result<Vec<_>, _>>()?;
+        coords.try_into().map(Point).map_err(|_| ())
+    }
+}
+
+/// A parser for `x,y,z` without whitespace, for the days' inputs
+pub fn point<'a, const N: usize>() -> impl Parser<'a, Point<N>> {
+    array(integer(), literal(",")).map(Point)
+}
+
+/// The straight line from `start` to `end`, both included
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Segment<const N: usize> {
+    pub start: Point<N>,
+    pub end: Point<N>,
+}
+
+impl<const N: usize> Segment<N> {
+    pub fn new(start: Point<N>, end: Point<N>) -> Self {
+        Segment { start, end }
+    }
+
+    /// Whether at most one coordinate changes along the segment, e.g. horizontal or vertical in 2D
+    pub fn is_axis_aligned(&self) -> bool {
+        (self.end - self.start)
+            .0
+            .iter()
+            .filter(|&&delta| delta != 0)
+            .count()
+            <= 1
+    }
+
+    /// The points from `start` to `end`, panics unless every changing coordinate changes by the
+    /// same amount, i.e. unless the segment is axis-aligned or diagonal at 45°
+    pub fn points(&self) -> impl Iterator<Item = Point<N>> {
+        let length = self.start.chebyshev(self.end);
+        let step = (self.end - self.start).signum();
+        assert_eq!(
+            self.start + step * length,
+            self.end,
+            "{:?} has no evenly spaced points",
+            self
+        );
+        let start = self.start;
+        (0..=length).map(move |idx| start + step * idx)
+    }
+}
+
+/// An axis-aligned box from `min` to `max`, both included, empty if `min` exceeds `max` on any
+/// axis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds<const N: usize> {
+    pub min: Point<N>,
+    pub max: Point<N>,
+}
+
+impl<const N: usize> Bounds<N> {
+    pub fn new(min: Point<N>, max: Point<N>) -> Self {
+        Bounds { min, max }
+    }
+
+    /// The smallest box containing all the points, `None` without points
+    pub fn around(points: impl IntoIterator<Item = Point<N>>) -> Option<Self> {
+        points.into_iter().fold(None, |bounds, point| {
+            Some(match bounds {
+                None => Bounds::new(point, point),
+                Some(Bounds { mut min, mut max }) => {
+                    for axis in 0..N {
+                        min[axis] = min[axis].min(point[axis]);
+                        max[axis] = max[axis].max(point[axis]);
+                    }
+                    Bounds::new(min, max)
+                }
+            })
+        })
+    }
+
+    pub fn range(&self, axis: usize) -> RangeInclusive<i64> {
+        self.min[axis]..=self.max[axis]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..N).any(|axis| self.min[axis] > self.max[axis])
+    }
+
+    pub fn contains(&self, point: &Point<N>) -> bool {
+        (0..N).all(|axis| self.range(axis).contains(&point[axis]))
+    }
+
+    /// The number of points inside
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+        (0..N)
+            .map(|axis| (self.max[axis] - self.min[axis] + 1) as u64)
+            .product()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut overlap = *self;
+        for axis in 0..N {
+            overlap.min[axis] = self.min[axis].max(other.min[axis]);
+            overlap.max[axis] = self.max[axis].min(other.max[axis]);
+        }
+        Some(overlap).filter(|overlap| !overlap.is_empty())
+    }
+
+    /// `self` without the points of `other`, as at most `2 * N` disjoint boxes
+    ///
+    /// The slabs below and above the overlap are cut off one axis after the other.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        let overlap = match self.intersection(other) {
+            Some(overlap) => overlap,
+            None => return vec![*self],
+        };
+        let mut rest = *self;
+        let mut parts = vec![];
+        for axis in 0..N {
+            if rest.min[axis] < overlap.min[axis] {
+                let mut below = rest;
+                below.max[axis] = overlap.min[axis] - 1;
+                parts.push(below);
+            }
+            if overlap.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = overlap.max[axis] + 1;
+                parts.push(above);
+            }
+            rest.min[axis] = overlap.min[axis];
+            rest.max[axis] = overlap.max[axis];
+        }
+        parts
+    }
+}
+
+/// A rotation in 3D by multiples of 90°, as matrix
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rotation(pub [[i64; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Self = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    /// rotations are orthogonal, so the inverse is the transpose
+    pub fn inverse(&self) -> Self {
+        let mut inverse = [[0; 3]; 3];
+        for (row, values) in self.0.iter().enumerate() {
+            for (column, &value) in values.iter().enumerate() {
+                inverse[column][row] = value;
+            }
+        }
+        Rotation(inverse)
+    }
+
+    fn determinant(&self) -> i64 {
+        let [a, b, c] = self.0;
+        a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+}
+
+/// The 24 orientations of a cube, the identity first
+///
+/// Those are the matrices with one 1 or -1 in every row and column and a determinant of 1, the
+/// other 24 mirror.
+pub fn rotations() -> impl Iterator<Item = Rotation> {
+    const PERMUTATIONS: [[usize; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    PERMUTATIONS
+        .into_iter()
+        .flat_map(|permutation| {
+            (0..8).map(move |signs: u8| {
+                let mut matrix = [[0; 3]; 3];
+                for (row, &column) in permutation.iter().enumerate() {
+                    matrix[row][column] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+                Rotation(matrix)
+            })
+        })
+        .filter(|rotation| rotation.determinant() == 1)
+}
+
+impl Mul<Point<3>> for Rotation {
+    type Output = Point<3>;
+
+    fn mul(self, point: Point<3>) -> Point<3> {
+        Point(
+            self.0
+                .map(|row| (0..3).map(|axis| row[axis] * point[axis]).sum()),
+        )
+    }
+}
+
+/// `a * b` rotates by `b` first
+impl Mul for Rotation {
+    type Output = Rotation;
+
+    fn mul(self, other: Rotation) -> Rotation {
+        let columns = other.inverse().0.map(|column| self * Point(column));
+        Rotation([0, 1, 2].map(|row| columns.map(|column| column[row])))
+    }
+}
+
+/// A rotation followed by a translation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Point<3>,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: Point::ORIGIN,
+    };
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.inverse();
+        Transform {
+            rotation,
+            translation: -(rotation * self.translation),
+        }
+    }
+
+    /// As a 4x4 matrix for homogeneous coordinates
+    pub fn matrix(&self) -> [[i64; 4]; 4] {
+        let [a, b, c] = self.rotation.0;
+        let Point([x, y, z]) = self.translation;
+        [
+            [a[0], a[1], a[2], x],
+            [b[0], b[1], b[2], y],
+            [c[0], c[1], c[2], z],
+            [0, 0, 0, 1],
+        ]
+    }
+}
+
+impl Mul<Point<3>> for Transform {
+    type Output = Point<3>;
+
+    fn mul(self, point: Point<3>) -> Point<3> {
+        self.rotation * point + self.translation
+    }
+}
+
+/// `a * b` transforms by `b` first
+impl Mul for Transform {
+    type Output = Transform;
+
+    fn mul(self, other: Transform) -> Transform {
+        Transform {
+            rotation: self.rotation * other.rotation,
+            translation: self * other.translation,
+        }
+    }
+}
+
+#[test]
+fn points_and_segments() {
+    let a: Point<2> = "1, -2".parse().unwrap();
+    assert_eq!(a, Point([1, -2]));
+    assert_eq!("1,2,3".parse::<Point<2>>(), Err(()));
+    assert_eq!(a.manhattan(Point([4, 2])), 7);
+
+    let diagonal = Segment::new(Point([3, 3]), Point([1, 5]));
+    assert!(!diagonal.is_axis_aligned());
+    assert_eq!(
+        diagonal.points().collect::<Vec<_>>(),
+        [Point([3, 3]), Point([2, 4]), Point([1, 5])]
+    );
+    assert!(Segment::new(Point([0, 0, 1]), Point([0, 0, 4])).is_axis_aligned());
+}
+
+#[test]
+fn box_difference() {
+    let outer = Bounds::new(Point([0, 0, 0]), Point([9, 9, 9]));
+    let inner = Bounds::new(Point([2, 3, 4]), Point([5, 12, 6]));
+    assert_eq!(
+        outer.intersection(&inner),
+        Some(Bounds::new(Point([2, 3, 4]), Point([5, 9, 6])))
+    );
+    let parts = outer.difference(&inner);
+    assert_eq!(parts.len(), 5);
+    assert_eq!(
+        parts.iter().map(Bounds::volume).sum::<u64>(),
+        1000 - 4 * 7 * 3
+    );
+    assert!(parts.iter().all(|part| part.intersection(&inner).is_none()));
+    assert_eq!(
+        Bounds::around([Point([1, 5]), Point([-1, 2])]),
+        Some(Bounds::new(Point([-1, 2]), Point([1, 5])))
+    );
+}
+
+#[test]
+fn cube_rotations() {
+    let all = rotations().collect::<Vec<_>>();
+    assert_eq!(all.len(), 24);
+    assert_eq!(all[0], Rotation::IDENTITY);
+    assert!(all
+        .iter()
+        .all(|rotation| *rotation * rotation.inverse() == Rotation::IDENTITY));
+    let images = all
+        .iter()
+        .map(|&rotation| rotation * Point([1, 2, 3]))
+        .collect::<Set<_>>();
+    assert_eq!(images.len(), 24);
+}
+
+#[test]
+fn transforms_compose() {
+    let rotation = rotations().nth(5).unwrap();
+    let a = Transform {
+        rotation,
+        translation: Point([1, 2, 3]),
+    };
+    let b = Transform {
+        rotation: rotation.inverse(),
+        translation: Point([-4, 0, 7]),
+    };
+    let point = Point([5, -6, 7]);
+    assert_eq!((a * b) * point, a * (b * point));
+    assert_eq!(a.inverse() * (a * point), point);
+    assert_eq!(Transform::IDENTITY.matrix()[3], [0, 0, 0, 1]);
+}
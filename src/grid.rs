@@ -0,0 +1,287 @@
+//! A rectangular grid stored row by row, for the days whose input is a grid of characters
+//!
+//! Positions are `(row, column)`. A grid can wrap around at its edges, like day 25's seafloor, or
+//! have a value for everything outside of it, like day 20's infinite image.
+
+use crate::prelude::*;
+use core::fmt::{self, Display, Formatter};
+use core::ops::{Index, IndexMut};
+
+/// `(row, column)`
+pub type Pos = (usize, usize);
+
+const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const SURROUNDING: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    wrapping: bool,
+    outside: Option<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut cell: impl FnMut(Pos) -> T) -> Self {
+        Grid {
+            cells: (0..height)
+                .flat_map(|row| (0..width).map(move |column| (row, column)))
+                .map(&mut cell)
+                .collect(),
+            width,
+            height,
+            wrapping: false,
+            outside: None,
+        }
+    }
+
+    /// One row per non-empty line, panics if the lines differ in length
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Self {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for line in input
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+        {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut cell));
+            let row_width = cells.len() - before;
+            assert_eq!(
+                *width.get_or_insert(row_width),
+                row_width,
+                "row {} differs in length",
+                height
+            );
+            height += 1;
+        }
+        Grid {
+            cells,
+            width: width.unwrap_or(0),
+            height,
+            wrapping: false,
+            outside: None,
+        }
+    }
+
+    /// Makes the edges wrap around, for [`Grid::offset`], [`Grid::at`] and the neighbours
+    pub fn wrapping(mut self) -> Self {
+        self.wrapping = true;
+        self
+    }
+
+    /// The value of every position outside of a grid that does not wrap, for [`Grid::at`]
+    pub fn with_outside(mut self, outside: T) -> Self {
+        self.outside = Some(outside);
+        self
+    }
+
+    pub fn outside(&self) -> Option<&T> {
+        self.outside.as_ref()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, column): Pos) -> Option<&T> {
+        (row < self.height && column < self.width).then(|| &self.cells[row * self.width + column])
+    }
+
+    pub fn get_mut(&mut self, (row, column): Pos) -> Option<&mut T> {
+        (row < self.height && column < self.width)
+            .then(|| &mut self.cells[row * self.width + column])
+    }
+
+    /// The cell at a position that may lie outside, `None` there unless the grid wraps or has an
+    /// outside value
+    pub fn at(&self, row: isize, column: isize) -> Option<&T> {
+        match self.offset((0, 0), (row, column)) {
+            Some(pos) => Some(&self[pos]),
+            None => self.outside.as_ref(),
+        }
+    }
+
+    /// `pos` moved by `(rows, columns)`, `None` if that leaves a grid that does not wrap
+    pub fn offset(&self, (row, column): Pos, (rows, columns): (isize, isize)) -> Option<Pos> {
+        let (height, width) = (self.height as isize, self.width as isize);
+        let (row, column) = (row as isize + rows, column as isize + columns);
+        if self.wrapping && !self.cells.is_empty() {
+            Some((
+                row.rem_euclid(height) as usize,
+                column.rem_euclid(width) as usize,
+            ))
+        } else if (0..height).contains(&row) && (0..width).contains(&column) {
+            Some((row as usize, column as usize))
+        } else {
+            None
+        }
+    }
+
+    /// The positions above, below, left and right of `pos` that are on the grid
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.moved(pos, &ORTHOGONAL)
+    }
+
+    /// The neighbours including the diagonal ones
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.moved(pos, &SURROUNDING)
+    }
+
+    /// `pos` moved by each of `deltas`, every position once and never `pos` itself. Those only
+    /// come up on a wrapping grid less than three cells wide or high, where moving both ways or
+    /// all the way round ends up on the same cell.
+    fn moved(&self, pos: Pos, deltas: &'static [(isize, isize)]) -> impl Iterator<Item = Pos> + '_ {
+        deltas.iter().enumerate().filter_map(move |(i, &delta)| {
+            let next = self.offset(pos, delta)?;
+            let repeated = self.wrapping
+                && (next == pos
+                    || deltas[..i]
+                        .iter()
+                        .any(|&earlier| self.offset(pos, earlier) == Some(next)));
+            (!repeated).then_some(next)
+        })
+    }
+
+    /// All positions, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    /// The cells with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn values(&self) -> core::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn values_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    /// A grid of the same shape, wrapping and outside value with every cell mapped
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(&mut f).collect(),
+            width: self.width,
+            height: self.height,
+            wrapping: self.wrapping,
+            outside: self.outside.as_ref().map(f),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid::from_fn(width, height, |_| value.clone())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside of the grid", pos))
+    }
+}
+
+/// The rows on separate lines, without a trailing newline
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (row, cells) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            for cell in cells {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn parse_and_display() {
+    let grid = Grid::parse("123\n456\n", |c| c.to_digit(10).unwrap());
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(1, 0)], 4);
+    assert_eq!(grid.get((0, 3)), None);
+    assert_eq!(grid.to_string(), "123\n456");
+}
+
+#[test]
+fn neighbourhoods() {
+    let grid = Grid::new(3, 3, 0);
+    assert_eq!(
+        grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+        [(1, 0), (0, 1)]
+    );
+    assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+    assert_eq!(grid.neighbours8((2, 2)).count(), 3);
+
+    let grid = grid.wrapping();
+    assert_eq!(
+        grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+        [(2, 0), (1, 0), (0, 2), (0, 1)]
+    );
+    assert_eq!(grid.offset((2, 2), (1, 1)), Some((0, 0)));
+}
+
+#[test]
+fn small_wrapping_neighbourhoods() {
+    let grid = Grid::new(4, 1, 0).wrapping();
+    assert_eq!(
+        grid.neighbours4((0, 1)).collect::<Vec<_>>(),
+        [(0, 0), (0, 2)]
+    );
+    assert_eq!(
+        grid.neighbours8((0, 1)).collect::<Vec<_>>(),
+        [(0, 0), (0, 2)]
+    );
+
+    let grid = Grid::new(4, 2, 0).wrapping();
+    assert_eq!(
+        grid.neighbours4((0, 1)).collect::<Vec<_>>(),
+        [(1, 1), (0, 0), (0, 2)]
+    );
+    assert_eq!(
+        grid.neighbours8((0, 1)).collect::<Vec<_>>(),
+        [(1, 0), (1, 1), (1, 2), (0, 0), (0, 2)]
+    );
+}
+
+#[test]
+fn outside_value() {
+    let grid = Grid::parse("#.\n.#", |c| c == '#');
+    assert_eq!(grid.at(-1, 0), None);
+    let grid = grid.with_outside(true);
+    assert_eq!(grid.at(1, 1), Some(&true));
+    assert_eq!(grid.at(0, 1), Some(&false));
+    assert_eq!(grid.at(-1, 5), Some(&true));
+}
@@ -0,0 +1,25 @@
+//! FNV-1a, a small non-cryptographic hash for recognizing unchanged inputs and sources
+//!
+//! Stable across builds and platforms, unlike `std`'s `DefaultHasher`, so hashes can be stored.
+//! Only uses `core` so that `build.rs` can include this file as well.
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub const fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = OFFSET_BASIS;
+    let mut idx = 0;
+    while idx < bytes.len() {
+        hash ^= bytes[idx] as u64;
+        hash = hash.wrapping_mul(PRIME);
+        idx += 1;
+    }
+    hash
+}
+
+#[test]
+fn known_hashes() {
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+}
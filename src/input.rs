@@ -0,0 +1,139 @@
+//! Harmless formatting differences in puzzle inputs, e.g. from copy pasting or editors on Windows
+//!
+//! Every input passes through [`normalize`] before it reaches a day's parser, so parsers only
+//! need to handle the canonical form: no byte order mark, `\n` line endings, no whitespace at
+//! the end of lines and no newline at the end of the input.
+//! [`anomalies`] reports what [`normalize`] would change, for the runner's strict mode.
+
+use crate::prelude::*;
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+
+/// Something [`normalize`] would fix, line numbers start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anomaly {
+    ByteOrderMark,
+    CarriageReturns { first_line: usize, count: usize },
+    TrailingWhitespace { first_line: usize, count: usize },
+    BlankLinesAtEnd(usize),
+}
+
+impl core::fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fn lines(
+            f: &mut core::fmt::Formatter<'_>,
+            first_line: usize,
+            count: usize,
+        ) -> core::fmt::Result {
+            match count {
+                1 => write!(f, "on line {}", first_line),
+                _ => write!(f, "on {} lines, first on line {}", count, first_line),
+            }
+        }
+
+        match *self {
+            Anomaly::ByteOrderMark => write!(f, "byte order mark at the start"),
+            Anomaly::CarriageReturns { first_line, count } => {
+                write!(f, "carriage return ")?;
+                lines(f, first_line, count)
+            }
+            Anomaly::TrailingWhitespace { first_line, count } => {
+                write!(f, "trailing whitespace ")?;
+                lines(f, first_line, count)
+            }
+            Anomaly::BlankLinesAtEnd(1) => write!(f, "blank line at the end"),
+            Anomaly::BlankLinesAtEnd(count) => write!(f, "{} blank lines at the end", count),
+        }
+    }
+}
+
+/// The canonical form of the input that the parsers expect
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix(BYTE_ORDER_MARK).unwrap_or(input);
+    let mut normalized = input
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    normalized.truncate(normalized.trim_end_matches('\n').len());
+    normalized
+}
+
+/// Everything [`normalize`] would change, empty for inputs already in canonical form
+/// (apart from a single newline at the end, which every downloaded input has)
+pub fn anomalies(input: &str) -> Vec<Anomaly> {
+    let mut anomalies = vec![];
+
+    if input.starts_with(BYTE_ORDER_MARK) {
+        anomalies.push(Anomaly::ByteOrderMark);
+    }
+
+    let mut carriage_returns = None;
+    let mut trailing_whitespace = None;
+    for (idx, line) in input.split('\n').enumerate() {
+        let line = match line.strip_suffix('\r') {
+            Some(line) => {
+                count(&mut carriage_returns, idx + 1);
+                line
+            }
+            None => line,
+        };
+        if line.len() != line.trim_end().len() {
+            count(&mut trailing_whitespace, idx + 1);
+        }
+    }
+    if let Some((first_line, count)) = carriage_returns {
+        anomalies.push(Anomaly::CarriageReturns { first_line, count });
+    }
+    if let Some((first_line, count)) = trailing_whitespace {
+        anomalies.push(Anomaly::TrailingWhitespace { first_line, count });
+    }
+
+    let end = &input[input.trim_end().len()..];
+    let blank_lines = end.matches('\n').count().saturating_sub(1);
+    if blank_lines > 0 {
+        anomalies.push(Anomaly::BlankLinesAtEnd(blank_lines));
+    }
+
+    anomalies
+}
+
+/// `(first_line, count)` of the lines with one kind of anomaly
+fn count(lines: &mut Option<(usize, usize)>, line: usize) {
+    lines.get_or_insert((line, 0)).1 += 1;
+}
+
+#[test]
+fn normalize_fixes_formatting() {
+    assert_eq!(
+        normalize("\u{feff}1,2 \r\n3\t\r\n\r\n4\r\n\r\n\n"),
+        "1,2\n3\n\n4"
+    );
+}
+
+#[test]
+fn canonical_input_unchanged() {
+    let input = "1,2\n\n3";
+    assert_eq!(normalize(input), input);
+    assert_eq!(anomalies(input), vec![]);
+    assert_eq!(anomalies("1,2\n\n3\n"), vec![]);
+}
+
+#[test]
+fn anomalies_found() {
+    assert_eq!(
+        anomalies("\u{feff}a \r\nb\r\nc\n\n\n"),
+        vec![
+            Anomaly::ByteOrderMark,
+            Anomaly::CarriageReturns {
+                first_line: 1,
+                count: 2
+            },
+            Anomaly::TrailingWhitespace {
+                first_line: 1,
+                count: 1
+            },
+            Anomaly::BlankLinesAtEnd(2),
+        ]
+    );
+}
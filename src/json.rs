@@ -0,0 +1,476 @@
+//! A small JSON parser and writer, for the leaderboard export and the runner's JSON output
+//!
+//! Objects keep their keys in document order. Numbers are `f64`, which holds the integers in
+//! the leaderboard, unix timestamps and scores, exactly.
+
+use crate::prelude::*;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// the value of `key` if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// the number if it is an integer
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_f64()
+            .filter(|&number| (-9.0e15..9.0e15).contains(&number) && number as i64 as f64 == number)
+            .map(|number| number as i64)
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Object(entries) => Some(entries),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(string: &str) -> Self {
+        Value::String(string.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(string: String) -> Self {
+        Value::String(string)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+macro_rules! from_integer {
+    ($($integer:ty),*) => {
+        $(impl From<$integer> for Value {
+            fn from(number: $integer) -> Self {
+                Value::Number(number as f64)
+            }
+        })*
+    };
+}
+
+from_integer!(u8, u32, u64, usize, i32, i64, isize);
+
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Value::Number(number)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<T: Into<Value>, const N: usize> From<[T; N]> for Value {
+    fn from(values: [T; N]) -> Self {
+        Value::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// An object with the keys in the given order
+pub fn object<const N: usize>(entries: [(&str, Value); N]) -> Value {
+    Value::Object(
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+    )
+}
+
+/// `text` as a JSON string literal
+pub fn escape(f: &mut impl fmt::Write, text: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in text.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// Compact JSON, with a space after `:` and `,` to stay readable on one line
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(number) if number.is_finite() => write!(f, "{}", number),
+            Value::Number(_) => write!(f, "null"),
+            Value::String(string) => escape(f, string),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ", ")?;
+                    }
+                    escape(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// What went wrong where, lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {} column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl core::error::Error for Error {}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> Error {
+        let before = &self.text[..self.pos];
+        Error {
+            line: before.matches('\n').count() + 1,
+            column: before.chars().rev().take_while(|&c| c != '\n').count() + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), Error> {
+        if self.text[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", token)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::String),
+            Some('t') => self.expect("true").map(|_| Value::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Value::Bool(false)),
+            Some('n') => self.expect("null").map(|_| Value::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// the items between `open` and `close`, separated by commas
+    fn list<T>(
+        &mut self,
+        open: &str,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, Error>,
+    ) -> Result<Vec<T>, Error> {
+        self.expect(open)?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    return Ok(items);
+                }
+                _ => return Err(self.error(&format!("expected , or {}", close))),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, Error> {
+        self.list("{", '}', |parser| {
+            parser.skip_whitespace();
+            let key = parser.string()?;
+            parser.skip_whitespace();
+            parser.expect(":")?;
+            Ok((key, parser.value()?))
+        })
+        .map(Value::Object)
+    }
+
+    fn array(&mut self) -> Result<Value, Error> {
+        self.list("[", ']', Parser::value).map(Value::Array)
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        if self.peek() != Some('"') {
+            return Err(self.error("expected a string"));
+        }
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    self.pos += 1;
+                    string.push(self.escape()?);
+                }
+                c if c.is_control() => return Err(self.error("control character in string")),
+                c => {
+                    self.pos += c.len_utf8();
+                    string.push(c);
+                }
+            }
+        }
+    }
+
+    /// the character of the escape sequence after a `\`
+    fn escape(&mut self) -> Result<char, Error> {
+        let c = self
+            .peek()
+            .ok_or_else(|| self.error("unterminated string"))?;
+        self.pos += 1;
+        Ok(match c {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let high = self.hex4()?;
+                let code = if (0xd800..0xdc00).contains(&high) {
+                    self.expect("\\u")?;
+                    let low = self.hex4()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.error("invalid low surrogate"));
+                    }
+                    0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+                } else {
+                    high
+                };
+                char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?
+            }
+            _ => {
+                // point at the backslash
+                self.pos -= 2;
+                return Err(self.error("invalid escape"));
+            }
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u32, Error> {
+        let digits = self
+            .text
+            .get(self.pos..self.pos + 4)
+            .filter(|digits| digits.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("expected 4 hex digits"))?;
+        self.pos += 4;
+        Ok(u32::from_str_radix(digits, 16).unwrap())
+    }
+
+    fn number(&mut self) -> Result<Value, Error> {
+        let rest = &self.text[self.pos..];
+        let len = number_len(rest).ok_or_else(|| self.error("invalid number"))?;
+        let number = rest[..len]
+            .parse()
+            .map_err(|_| self.error("invalid number"))?;
+        self.pos += len;
+        Ok(Value::Number(number))
+    }
+}
+
+/// the length of the number at the start of `text`, as RFC 8259 has them:
+/// `-? (0 | [1-9][0-9]*) (\.[0-9]+)? ([eE][+-]?[0-9]+)?`
+fn number_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let digits = |from: usize| {
+        bytes[from.min(bytes.len())..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let mut len = usize::from(bytes.first() == Some(&b'-'));
+    match digits(len) {
+        0 => return None,
+        // no leading zeros
+        count if count > 1 && bytes[len] == b'0' => return None,
+        count => len += count,
+    }
+    if bytes.get(len) == Some(&b'.') {
+        match digits(len + 1) {
+            0 => return None,
+            count => len += 1 + count,
+        }
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        len += 1;
+        if matches!(bytes.get(len), Some(b'+' | b'-')) {
+            len += 1;
+        }
+        match digits(len) {
+            0 => return None,
+            count => len += count,
+        }
+    }
+    Some(len)
+}
+
+pub fn parse(text: &str) -> Result<Value, Error> {
+    let mut parser = Parser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < text.len() {
+        return Err(parser.error("unexpected text after the value"));
+    }
+    Ok(value)
+}
+
+#[test]
+fn parse_values() {
+    let value =
+        parse(r#" {"a": [1, -2.5e1, true, null], "b": {"c": "x\"\u00e9\ud83c\udf84"}} "#).unwrap();
+    assert_eq!(
+        value.get("a"),
+        Some(&Value::Array(vec![
+            Value::Number(1.0),
+            Value::Number(-25.0),
+            Value::Bool(true),
+            Value::Null
+        ]))
+    );
+    assert_eq!(
+        value
+            .get("b")
+            .and_then(|b| b.get("c"))
+            .and_then(Value::as_str),
+        Some("x\"é🎄")
+    );
+    assert_eq!(parse("1638334800").unwrap().as_i64(), Some(1638334800));
+}
+
+#[test]
+fn errors_have_positions() {
+    let error = parse("{\n  \"a\": [1, 2\n}").unwrap_err();
+    assert_eq!((error.line, error.column), (3, 1));
+    assert_eq!(error.to_string(), "line 3 column 1: expected , or ]");
+    assert_eq!(
+        parse("[1] 2").unwrap_err().message,
+        "unexpected text after the value"
+    );
+    assert_eq!(parse("\"\\x\"").unwrap_err().column, 2);
+}
+
+#[test]
+fn numbers_follow_the_rfc() {
+    for (text, number) in [("0", 0.0), ("-0.5", -0.5), ("1E-2", 0.01), ("2e+3", 2000.0)] {
+        assert_eq!(parse(text), Ok(Value::Number(number)), "{}", text);
+    }
+    for text in ["+1", "1.", ".5", "01", "-01", "-", "1e", "1e+", "-.5"] {
+        assert!(parse(text).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn display_round_trip() {
+    let text = r#"{"name": "a \"b\"\n", "stars": [1, 2.5], "ok": false, "none": null}"#;
+    let value = parse(text).unwrap();
+    assert_eq!(value.to_string(), text);
+    assert_eq!(parse(&value.to_string()).unwrap(), value);
+}
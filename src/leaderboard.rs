@@ -0,0 +1,467 @@
+//! Analysis of a private leaderboard saved from `https://adventofcode.com/2021/leaderboard/private/view/<id>.json`
+//!
+//! ```json
+//! {"event": "2021", "owner_id": 1745334, "members": {"1745334": {
+//!     "id": 1745334, "name": "someone", "stars": 2, "local_score": 10,
+//!     "completion_day_level": {"1": {"1": {"get_star_ts": 1638335042}, "2": {"get_star_ts": 1638335310}}}
+//! }}}
+//! ```
+//!
+//! Times are unix timestamps, puzzles unlock at midnight EST, which is 05:00 UTC.
+
+use crate::json::{self, Value};
+use crate::prelude::*;
+use alloc::collections::BTreeMap;
+
+/// seconds from 00:00 UTC until a puzzle unlocks
+const UNLOCK_OFFSET: i64 = 5 * 3600;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for anonymous users
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: i64,
+    /// the timestamps of the part 1 and part 2 stars by day
+    pub completions: BTreeMap<u8, [Option<i64>; 2]>,
+}
+
+impl Member {
+    /// the name, or how adventofcode.com shows anonymous users
+    pub fn display_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.clone(),
+            None => format!("(anonymous user #{})", self.id),
+        }
+    }
+
+    pub fn star_time(&self, day: u8, part: u8) -> Option<i64> {
+        let parts = self.completions.get(&day)?;
+        *parts.get(usize::from(part).checked_sub(1)?)?
+    }
+
+    fn last_star_time(&self) -> i64 {
+        self.completions
+            .values()
+            .flatten()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(i64::MAX)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leaderboard {
+    pub year: i64,
+    pub owner_id: u64,
+    /// sorted by id
+    pub members: Vec<Member>,
+}
+
+impl Leaderboard {
+    pub fn member(&self, id: u64) -> Option<&Member> {
+        self.members.iter().find(|member| member.id == id)
+    }
+
+    /// seconds from the puzzle's unlock until the star
+    pub fn solve_time(&self, member: &Member, day: u8, part: u8) -> Option<i64> {
+        Some(member.star_time(day, part)? - unlock_time(self.year, day))
+    }
+}
+
+/// ids and timestamps are numbers, older exports have them as strings
+fn integer(value: &Value) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str()?.parse().ok())
+}
+
+fn member(value: &Value) -> Result<Member, String> {
+    let id = value
+        .get("id")
+        .and_then(integer)
+        .ok_or("member without an id")? as u64;
+    let field = |name: &str| {
+        value
+            .get(name)
+            .and_then(integer)
+            .ok_or_else(|| format!("member {} has no {}", id, name))
+    };
+
+    let mut completions = BTreeMap::new();
+    let days = value
+        .get("completion_day_level")
+        .and_then(Value::as_object)
+        .unwrap_or_default();
+    for (day, parts) in days {
+        let day = day
+            .parse::<u8>()
+            .map_err(|_| format!("member {} has an invalid day {:?}", id, day))?;
+        let star = |part: &str| {
+            parts
+                .get(part)
+                .map(|star| {
+                    star.get("get_star_ts")
+                        .and_then(integer)
+                        .ok_or_else(|| format!("member {} day {} has no get_star_ts", id, day))
+                })
+                .transpose()
+        };
+        completions.insert(day, [star("1")?, star("2")?]);
+    }
+
+    Ok(Member {
+        id,
+        name: value.get("name").and_then(Value::as_str).map(String::from),
+        stars: field("stars")? as u32,
+        local_score: field("local_score")?,
+        completions,
+    })
+}
+
+pub fn parse(text: &str) -> Result<Leaderboard, String> {
+    let value = json::parse(text).map_err(|err| err.to_string())?;
+    let year = value
+        .get("event")
+        .and_then(integer)
+        .ok_or("no event year")?;
+    let owner_id = value
+        .get("owner_id")
+        .and_then(integer)
+        .ok_or("no owner_id")? as u64;
+    let mut members = value
+        .get("members")
+        .and_then(Value::as_object)
+        .ok_or("no members")?
+        .iter()
+        .map(|(_, value)| member(value))
+        .collect::<Result<Vec<_>, _>>()?;
+    members.sort_by_key(|member| member.id);
+
+    Ok(Leaderboard {
+        year,
+        owner_id,
+        members,
+    })
+}
+
+/// days since 1970-01-01 of a date in the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// when the puzzle of the day unlocks
+pub fn unlock_time(year: i64, day: u8) -> i64 {
+    days_from_civil(year, 12, day.into()) * 86400 + UNLOCK_OFFSET
+}
+
+/// `1:02:03`, with days in front for more than 24 hours, e.g. `3d 01:02:03`
+pub fn format_duration(seconds: i64) -> String {
+    let (days, rest) = (seconds / 86400, seconds % 86400);
+    let time = |hours_width| {
+        format!(
+            "{:0width$}:{:02}:{:02}",
+            rest / 3600,
+            rest / 60 % 60,
+            rest % 60,
+            width = hours_width
+        )
+    };
+    match days {
+        0 => time(1),
+        _ => format!("{}d {}", days, time(2)),
+    }
+}
+
+/// A star in a member's timeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Star {
+    pub day: u8,
+    pub part: u8,
+    pub timestamp: i64,
+}
+
+/// the member's stars in the order they were earned
+pub fn timeline(member: &Member) -> Vec<Star> {
+    let mut stars = member
+        .completions
+        .iter()
+        .flat_map(|(&day, parts)| {
+            (1..=2).zip(parts).filter_map(move |(part, timestamp)| {
+                Some(Star {
+                    day,
+                    part,
+                    timestamp: (*timestamp)?,
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+    stars.sort_by_key(|star| (star.timestamp, star.day, star.part));
+    stars
+}
+
+/// seconds from the part 1 star to the part 2 star, for every day with both
+pub fn deltas(member: &Member) -> Vec<(u8, i64)> {
+    member
+        .completions
+        .iter()
+        .filter_map(|(&day, parts)| Some((day, parts[1]? - parts[0]?)))
+        .collect()
+}
+
+/// How to rank the members
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// adventofcode.com's local score: for each star, one point per member who got it later,
+    /// plus one
+    Local,
+    /// the most stars, ties go to whoever got their last star first
+    Stars,
+    /// the least total time from unlock to star, among the members with the most stars
+    SolveTime,
+    /// the least total time from part 1 to part 2, among the members with the most days with
+    /// both stars
+    Delta,
+}
+
+impl Scoring {
+    pub const ALL: [Scoring; 4] = [
+        Scoring::Local,
+        Scoring::Stars,
+        Scoring::SolveTime,
+        Scoring::Delta,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scoring::Local => "local",
+            Scoring::Stars => "stars",
+            Scoring::SolveTime => "time",
+            Scoring::Delta => "delta",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Scoring::ALL
+            .into_iter()
+            .find(|scoring| scoring.name() == name)
+    }
+
+    /// whether the score is a number of seconds
+    pub fn is_duration(self) -> bool {
+        matches!(self, Scoring::SolveTime | Scoring::Delta)
+    }
+}
+
+/// local scores computed from the star times, they match the export's `local_score`
+fn local_scores(leaderboard: &Leaderboard) -> BTreeMap<u64, i64> {
+    let mut scores = leaderboard
+        .members
+        .iter()
+        .map(|member| (member.id, 0))
+        .collect::<BTreeMap<_, _>>();
+    let count = leaderboard.members.len() as i64;
+
+    for day in 1..=25 {
+        for part in 1..=2 {
+            let mut finishers = leaderboard
+                .members
+                .iter()
+                .filter_map(|member| Some((member.star_time(day, part)?, member.id)))
+                .collect::<Vec<_>>();
+            finishers.sort();
+            for (rank, (_, id)) in finishers.into_iter().enumerate() {
+                *scores.get_mut(&id).unwrap() += count - rank as i64;
+            }
+        }
+    }
+    scores
+}
+
+/// The members ranked best first with their scores
+pub fn ranking(leaderboard: &Leaderboard, scoring: Scoring) -> Vec<(&Member, i64)> {
+    // sort key, lower is better, and the shown score
+    let mut ranked = match scoring {
+        Scoring::Local => {
+            let scores = local_scores(leaderboard);
+            leaderboard
+                .members
+                .iter()
+                .map(|member| ((-scores[&member.id], 0), member, scores[&member.id]))
+                .collect::<Vec<_>>()
+        }
+        Scoring::Stars => leaderboard
+            .members
+            .iter()
+            .map(|member| {
+                let stars = i64::from(member.stars);
+                ((-stars, member.last_star_time()), member, stars)
+            })
+            .collect(),
+        Scoring::SolveTime => leaderboard
+            .members
+            .iter()
+            .map(|member| {
+                let total = timeline(member)
+                    .iter()
+                    .map(|star| star.timestamp - unlock_time(leaderboard.year, star.day))
+                    .sum::<i64>();
+                ((-i64::from(member.stars), total), member, total)
+            })
+            .collect(),
+        Scoring::Delta => leaderboard
+            .members
+            .iter()
+            .map(|member| {
+                let deltas = deltas(member);
+                let total = deltas.iter().map(|(_, delta)| delta).sum::<i64>();
+                ((-(deltas.len() as i64), total), member, total)
+            })
+            .collect(),
+    };
+    ranked.sort_by_key(|&(key, member, _)| (key, member.id));
+    ranked
+        .into_iter()
+        .map(|(_, member, score)| (member, score))
+        .collect()
+}
+
+/// How one member's time on a part compares with everyone else's
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comparison {
+    pub day: u8,
+    pub part: u8,
+    /// seconds from unlock to star
+    pub own: Option<i64>,
+    pub best: Option<i64>,
+    pub median: Option<i64>,
+    /// 1 for the first to get the star, `None` without it
+    pub rank: Option<usize>,
+    /// how many members got the star
+    pub finishers: usize,
+}
+
+/// The member's solve times next to the team's, for every part anyone solved
+pub fn compare(leaderboard: &Leaderboard, id: u64) -> Option<Vec<Comparison>> {
+    let own = leaderboard.member(id)?;
+    let mut comparisons = vec![];
+
+    for day in 1..=25 {
+        for part in 1..=2 {
+            let mut times = leaderboard
+                .members
+                .iter()
+                .filter_map(|member| leaderboard.solve_time(member, day, part))
+                .collect::<Vec<_>>();
+            if times.is_empty() {
+                continue;
+            }
+            times.sort_unstable();
+
+            let own_time = leaderboard.solve_time(own, day, part);
+            comparisons.push(Comparison {
+                day,
+                part,
+                own: own_time,
+                best: times.first().copied(),
+                median: times.get(times.len() / 2).copied(),
+                rank: own_time.map(|own| times.partition_point(|&time| time < own) + 1),
+                finishers: times.len(),
+            });
+        }
+    }
+    Some(comparisons)
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = r#"{"event": "2021", "owner_id": 1, "members": {
+    "1": {"id": 1, "name": "alice", "stars": 3, "local_score": 8, "global_score": 0,
+          "last_star_ts": 1638421300, "completion_day_level": {
+            "1": {"1": {"get_star_ts": 1638335000, "star_index": 0},
+                  "2": {"get_star_ts": 1638335600, "star_index": 1}},
+            "2": {"1": {"get_star_ts": 1638421300, "star_index": 2}}}},
+    "2": {"id": 2, "name": null, "stars": 4, "local_score": 10, "global_score": 0,
+          "last_star_ts": "1638424000", "completion_day_level": {
+            "1": {"1": {"get_star_ts": "1638334900"}, "2": {"get_star_ts": "1638338500"}},
+            "2": {"1": {"get_star_ts": "1638421400"}, "2": {"get_star_ts": "1638424000"}}}},
+    "3": {"id": 3, "name": "carol", "stars": 0, "local_score": 0, "global_score": 0,
+          "last_star_ts": 0, "completion_day_level": {}}
+}}"#;
+
+#[test]
+fn parse_export() {
+    let leaderboard = parse(EXAMPLE).unwrap();
+    assert_eq!((leaderboard.year, leaderboard.owner_id), (2021, 1));
+    assert_eq!(leaderboard.members.len(), 3);
+    assert_eq!(leaderboard.members[1].display_name(), "(anonymous user #2)");
+    assert_eq!(leaderboard.members[1].star_time(2, 2), Some(1638424000));
+    assert_eq!(leaderboard.members[0].star_time(2, 2), None);
+    assert!(parse(r#"{"event": "2021"}"#).is_err());
+}
+
+#[test]
+fn unlock_times() {
+    // 2021-12-01T05:00:00Z
+    assert_eq!(unlock_time(2021, 1), 1638334800);
+    assert_eq!(unlock_time(2021, 25) - unlock_time(2021, 1), 24 * 86400);
+    assert_eq!(format_duration(3723), "1:02:03");
+    assert_eq!(format_duration(3 * 86400 + 60), "3d 00:01:00");
+}
+
+#[test]
+fn timelines_and_deltas() {
+    let leaderboard = parse(EXAMPLE).unwrap();
+    let alice = &leaderboard.members[0];
+    let days = timeline(alice)
+        .iter()
+        .map(|star| (star.day, star.part))
+        .collect::<Vec<_>>();
+    assert_eq!(days, [(1, 1), (1, 2), (2, 1)]);
+    assert_eq!(deltas(alice), [(1, 600)]);
+    assert_eq!(deltas(&leaderboard.members[1]), [(1, 3600), (2, 2600)]);
+}
+
+#[test]
+fn rankings() {
+    let leaderboard = parse(EXAMPLE).unwrap();
+    let ids = |scoring| {
+        ranking(&leaderboard, scoring)
+            .iter()
+            .map(|(member, score)| (member.id, *score))
+            .collect::<Vec<_>>()
+    };
+    // the computed local scores match the export
+    for (member, score) in ranking(&leaderboard, Scoring::Local) {
+        assert_eq!(member.local_score, score);
+    }
+    assert_eq!(ids(Scoring::Local), [(2, 10), (1, 8), (3, 0)]);
+    assert_eq!(ids(Scoring::Stars), [(2, 4), (1, 3), (3, 0)]);
+    assert_eq!(ids(Scoring::Delta)[..2], [(2, 6200), (1, 600)]);
+    assert_eq!(Scoring::from_name("time"), Some(Scoring::SolveTime));
+}
+
+#[test]
+fn compare_with_team() {
+    let leaderboard = parse(EXAMPLE).unwrap();
+    let comparisons = compare(&leaderboard, 1).unwrap();
+    assert_eq!(comparisons.len(), 4);
+    assert_eq!(
+        comparisons[0],
+        Comparison {
+            day: 1,
+            part: 1,
+            own: Some(200),
+            best: Some(100),
+            median: Some(200),
+            rank: Some(2),
+            finishers: 2,
+        }
+    );
+    assert_eq!(comparisons[3].rank, None);
+    assert!(compare(&leaderboard, 9).is_none());
+}
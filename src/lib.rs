@@ -1,3 +1,15 @@
+//! Solutions for Advent of Code 2021
+//!
+//! The solvers only need `alloc`, `std` is a default feature used by the runner, the budget's
+//! timeouts and the [`plugin`] loader. Check the `no_std` build with
+//! `cargo build --lib --no-default-features`. The C ABI is the cdylib in `ffi/`, which uses `std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod budget;
+#[cfg(any(feature = "std", test))]
+pub mod config;
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -23,3 +35,26 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+pub mod expected;
+pub mod geom;
+pub mod grid;
+pub mod hash;
+pub mod input;
+pub mod json;
+pub mod leaderboard;
+pub mod parser;
+pub mod perf;
+#[cfg(feature = "std")]
+pub mod plugin;
+mod prelude;
+pub mod registry;
+pub mod repl;
+pub mod rng;
+pub mod seal;
+pub mod search;
+pub mod writeup;
+
+#[cfg(test)]
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
@@ -0,0 +1,409 @@
+//! Parser combinators for the puzzle formats
+//!
+//! A parser reads the whole input from an offset, so a failure knows where it happened and
+//! [`parse_all`] can report its line and column. Parsers are functions or closures
+//! `Fn(&str, usize) -> Parsed<T>`, put together with the free functions here and the methods of
+//! [`Parser`]. Items of [`separated`] backtrack like alternatives do, the lines of [`lines`] and
+//! the blocks of [`blocks`] commit to their item, so a broken line is reported where it breaks.
+
+use crate::prelude::*;
+use core::cmp::Ordering;
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+/// Where a parser failed and what it expected there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub offset: usize,
+    pub expected: String,
+}
+
+/// The value and the offset after it
+pub type Parsed<T> = Result<(T, usize), Failure>;
+
+pub fn fail<T>(offset: usize, expected: impl Into<String>) -> Parsed<T> {
+    Err(Failure {
+        offset,
+        expected: expected.into(),
+    })
+}
+
+pub trait Parser<'a, T>: Sized {
+    fn parse_at(&self, input: &'a str, offset: usize) -> Parsed<T>;
+
+    fn map<U>(self, f: impl Fn(T) -> U) -> impl Parser<'a, U> {
+        move |input: &'a str, offset: usize| {
+            let (value, offset) = self.parse_at(input, offset)?;
+            Ok((f(value), offset))
+        }
+    }
+
+    /// Maps with a check, failing where the value starts with `Err(expected)`
+    fn try_map<U>(self, f: impl Fn(T) -> Result<U, &'static str>) -> impl Parser<'a, U> {
+        move |input: &'a str, start: usize| {
+            let (value, offset) = self.parse_at(input, start)?;
+            match f(value) {
+                Ok(value) => Ok((value, offset)),
+                Err(expected) => fail(start, expected),
+            }
+        }
+    }
+
+    /// Both values, this one first
+    fn and<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, (T, U)> {
+        move |input: &'a str, offset: usize| {
+            let (first, offset) = self.parse_at(input, offset)?;
+            let (second, offset) = next.parse_at(input, offset)?;
+            Ok(((first, second), offset))
+        }
+    }
+
+    /// This value, followed by `next`
+    fn skip<U>(self, next: impl Parser<'a, U>) -> impl Parser<'a, T> {
+        self.and(next).map(|(value, _)| value)
+    }
+
+    /// `other` where this one fails, if both do the failure that got further is kept
+    fn or(self, other: impl Parser<'a, T>) -> impl Parser<'a, T> {
+        move |input: &'a str, offset: usize| match self.parse_at(input, offset) {
+            Ok(parsed) => Ok(parsed),
+            Err(first) => other.parse_at(input, offset).map_err(|second| {
+                match first.offset.cmp(&second.offset) {
+                    Ordering::Greater => first,
+                    Ordering::Less => second,
+                    Ordering::Equal => Failure {
+                        offset: first.offset,
+                        expected: format!("{} or {}", first.expected, second.expected),
+                    },
+                }
+            }),
+        }
+    }
+
+    /// `None` where this one fails, without consuming anything
+    fn optional(self) -> impl Parser<'a, Option<T>> {
+        move |input: &'a str, offset: usize| match self.parse_at(input, offset) {
+            Ok((value, offset)) => Ok((Some(value), offset)),
+            Err(_) => Ok((None, offset)),
+        }
+    }
+}
+
+impl<'a, T, F: Fn(&'a str, usize) -> Parsed<T>> Parser<'a, T> for F {
+    fn parse_at(&self, input: &'a str, offset: usize) -> Parsed<T> {
+        self(input, offset)
+    }
+}
+
+/// Exactly `text`
+pub fn literal<'a>(text: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str, offset: usize| {
+        if input[offset..].starts_with(text) {
+            Ok((&input[offset..offset + text.len()], offset + text.len()))
+        } else {
+            fail(offset, format!("`{}`", text.escape_debug()))
+        }
+    }
+}
+
+/// The longest non-empty run of characters matching `pred`, `expected` describes one of them
+pub fn take_while1<'a>(
+    expected: &'static str,
+    pred: impl Fn(char) -> bool,
+) -> impl Parser<'a, &'a str> {
+    move |input: &'a str, offset: usize| {
+        let rest = &input[offset..];
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        if len == 0 {
+            return fail(offset, expected);
+        }
+        Ok((&rest[..len], offset + len))
+    }
+}
+
+/// One character matching `pred`, `expected` describes it
+pub fn satisfy<'a>(expected: &'static str, pred: impl Fn(char) -> bool) -> impl Parser<'a, char> {
+    move |input: &'a str, offset: usize| match input[offset..].chars().next() {
+        Some(c) if pred(c) => Ok((c, offset + c.len_utf8())),
+        _ => fail(offset, expected),
+    }
+}
+
+/// Whatever is left of the input, possibly nothing
+pub fn rest<'a>() -> impl Parser<'a, &'a str> {
+    |input: &'a str, offset: usize| Ok((&input[offset..], input.len()))
+}
+
+/// One or more spaces
+pub fn spaces<'a>() -> impl Parser<'a, &'a str> {
+    take_while1("a space", |c| c == ' ')
+}
+
+/// Decimal digits with an optional `-`, failing if they do not fit into `T`
+pub fn integer<'a, T: FromStr>() -> impl Parser<'a, T> {
+    move |input: &'a str, offset: usize| {
+        let rest = &input[offset..];
+        let sign = usize::from(rest.starts_with('-'));
+        let digits = rest[sign..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return fail(offset, "an integer");
+        }
+        match rest[..sign + digits].parse() {
+            Ok(value) => Ok((value, offset + sign + digits)),
+            // an unsigned `T` rejects every negative number, even -1
+            Err(_) if sign == 1 && "-1".parse::<T>().is_err() => {
+                fail(offset, "an unsigned integer")
+            }
+            Err(_) => fail(offset, "a smaller integer"),
+        }
+    }
+}
+
+/// The value of the first of `choices` whose text comes next
+pub fn one_of<'a, T: Clone>(choices: &'static [(&'static str, T)]) -> impl Parser<'a, T> {
+    move |input: &'a str, offset: usize| {
+        let rest = &input[offset..];
+        match choices.iter().find(|(text, _)| rest.starts_with(text)) {
+            Some((text, value)) => Ok((value.clone(), offset + text.len())),
+            None => {
+                let texts = choices
+                    .iter()
+                    .map(|(text, _)| format!("`{}`", text.escape_debug()))
+                    .collect::<Vec<_>>();
+                fail(offset, format!("one of {}", texts.join(", ")))
+            }
+        }
+    }
+}
+
+/// `item` after `prefix`
+pub fn preceded<'a, P, T>(
+    prefix: impl Parser<'a, P>,
+    item: impl Parser<'a, T>,
+) -> impl Parser<'a, T> {
+    prefix.and(item).map(|(_, value)| value)
+}
+
+/// Zero or more `item`s
+pub fn many<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str, mut offset: usize| {
+        let mut items = vec![];
+        while let Ok((value, next)) = item.parse_at(input, offset) {
+            if next == offset {
+                break;
+            }
+            items.push(value);
+            offset = next;
+        }
+        Ok((items, offset))
+    }
+}
+
+/// One or more `item`s
+pub fn many1<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str, offset: usize| {
+        let (first, mut offset) = item.parse_at(input, offset)?;
+        let mut items = vec![first];
+        while let Ok((value, next)) = item.parse_at(input, offset) {
+            if next == offset {
+                break;
+            }
+            items.push(value);
+            offset = next;
+        }
+        Ok((items, offset))
+    }
+}
+
+/// One or more `item`s between `separator`s, a separator not followed by an item is left alone
+pub fn separated<'a, T, S>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str, offset: usize| {
+        let (first, mut offset) = item.parse_at(input, offset)?;
+        let mut items = vec![first];
+        while let Ok((value, next)) = separator
+            .parse_at(input, offset)
+            .and_then(|(_, after)| item.parse_at(input, after))
+        {
+            items.push(value);
+            offset = next;
+        }
+        Ok((items, offset))
+    }
+}
+
+/// Exactly `N` items between `separator`s
+pub fn array<'a, T, S, const N: usize>(
+    item: impl Parser<'a, T>,
+    separator: impl Parser<'a, S>,
+) -> impl Parser<'a, [T; N]> {
+    move |input: &'a str, start: usize| {
+        let mut items = Vec::with_capacity(N);
+        let mut offset = start;
+        for idx in 0..N {
+            if idx > 0 {
+                offset = separator.parse_at(input, offset)?.1;
+            }
+            let (value, next) = item.parse_at(input, offset)?;
+            items.push(value);
+            offset = next;
+        }
+        match items.try_into() {
+            Ok(items) => Ok((items, offset)),
+            Err(_) => unreachable!("exactly N items were parsed"),
+        }
+    }
+}
+
+/// the end of the current line, without consuming the newline
+fn end_of_line(input: &str, offset: usize) -> Parsed<()> {
+    match input[offset..].chars().next() {
+        None | Some('\n') => Ok(((), offset)),
+        Some(_) => fail(offset, "the end of the line"),
+    }
+}
+
+/// One or more lines that each hold an `item`, up to the end of the input or a blank line
+pub fn lines<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    let line = item.skip(end_of_line);
+    move |input: &'a str, offset: usize| {
+        let (first, mut offset) = line.parse_at(input, offset)?;
+        let mut items = vec![first];
+        while let Some(rest) = input[offset..].strip_prefix('\n') {
+            if rest.is_empty() || rest.starts_with('\n') {
+                break;
+            }
+            let (value, next) = line.parse_at(input, offset + 1)?;
+            items.push(value);
+            offset = next;
+        }
+        Ok((items, offset))
+    }
+}
+
+/// One or more `item`s separated by blank lines
+pub fn blocks<'a, T>(item: impl Parser<'a, T>) -> impl Parser<'a, Vec<T>> {
+    move |input: &'a str, offset: usize| {
+        let (first, mut offset) = item.parse_at(input, offset)?;
+        let mut items = vec![first];
+        while input[offset..].starts_with("\n\n") {
+            let (value, next) = item.parse_at(input, offset + 2)?;
+            items.push(value);
+            offset = next;
+        }
+        Ok((items, offset))
+    }
+}
+
+/// A failure of [`parse_all`], lines and columns start at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    /// the rest of the line where the parser failed
+    pub found: String,
+}
+
+impl Error {
+    fn new(input: &str, failure: Failure) -> Self {
+        let before = &input[..failure.offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let rest = &input[failure.offset..];
+        Error {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: failure.expected,
+            found: rest.split('\n').next().unwrap_or(rest).to_string(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        match self.found.as_str() {
+            "" => write!(f, "the end of the line"),
+            found if found.chars().count() > 20 => {
+                write!(f, "`{}...`", found.chars().take(20).collect::<String>())
+            }
+            found => write!(f, "`{}`", found),
+        }
+    }
+}
+
+/// Runs `parser` on the whole input, only whitespace may follow what it parsed
+pub fn parse_all<'a, T>(parser: impl Parser<'a, T>, input: &'a str) -> Result<T, Error> {
+    let (value, offset) = parser
+        .parse_at(input, 0)
+        .map_err(|failure| Error::new(input, failure))?;
+    if !input[offset..].trim_end().is_empty() {
+        return Err(Error::new(
+            input,
+            Failure {
+                offset,
+                expected: String::from("the end of the input"),
+            },
+        ));
+    }
+    Ok(value)
+}
+
+/// [`parse_all`] for the days' `parse` functions, panics with the position of invalid input
+pub fn parse_or_panic<'a, T>(parser: impl Parser<'a, T>, input: &'a str) -> T {
+    parse_all(parser, input).unwrap_or_else(|err| panic!("invalid input, {}", err))
+}
+
+#[test]
+fn lists_and_lines() {
+    let numbers = separated(integer::<i32>(), literal(","));
+    assert_eq!(parse_all(numbers, "3,-4,5\n"), Ok(vec![3, -4, 5]));
+    let pairs = lines(array::<_, _, 2>(integer::<u8>(), literal(" -> ")));
+    assert_eq!(parse_all(pairs, "1 -> 2\n3 -> 4"), Ok(vec![[1, 2], [3, 4]]));
+    let groups = blocks(lines(integer::<u8>()));
+    assert_eq!(
+        parse_all(groups, "1\n2\n\n3"),
+        Ok(vec![vec![1, 2], vec![3]])
+    );
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let err = parse_all(lines(integer::<u8>()), "12\n34\n5x").unwrap_err();
+    assert_eq!((err.line, err.column), (3, 2));
+    assert_eq!(
+        err.to_string(),
+        "line 3, column 2: expected the end of the line, found `x`"
+    );
+
+    let err = parse_all(lines(integer::<u8>()), "12\n300").unwrap_err();
+    assert_eq!(err.expected, "a smaller integer");
+    let err = parse_all(integer::<u8>(), "-3").unwrap_err();
+    assert_eq!(err.expected, "an unsigned integer");
+    let err = parse_all(integer::<i8>(), "-300").unwrap_err();
+    assert_eq!(err.expected, "a smaller integer");
+
+    let direction = one_of(&[("up", 0), ("down", 1)]).or(literal("left").map(|_| 2));
+    let err = parse_all(direction, "right").unwrap_err();
+    assert_eq!(err.expected, "one of `up`, `down` or `left`");
+}
+
+#[test]
+fn alternatives_and_options() {
+    let signed = || literal("+").optional().and(integer::<u32>());
+    assert_eq!(parse_all(signed(), "+3"), Ok((Some("+"), 3)));
+    assert_eq!(parse_all(signed(), "3"), Ok((None, 3)));
+    let word = take_while1("a letter", char::is_alphabetic);
+    assert_eq!(
+        parse_all(separated(word, spaces()).skip(rest()), "ab  cd | x"),
+        Ok(vec!["ab", "cd"])
+    );
+}
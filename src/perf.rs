@@ -0,0 +1,220 @@
+//! The history of bench results across commits, kept by `run bench` and shown by
+//! `run perf-history`
+//!
+//! One tab separated record per bench run and solver, with the unix time of the run, the commit,
+//! the machine and the median runtime in nanoseconds:
+//!
+//! ```text
+//! 1697000000  3f3253a...  4c1d...  7  1  default  123456
+//! ```
+//!
+//! Runs on a working tree with uncommitted changes have [`DIRTY`] after the commit, they are kept
+//! but not attributed to the commit.
+
+use crate::prelude::*;
+use core::fmt;
+
+/// the suffix of the commit of a run with uncommitted changes
+pub const DIRTY: &str = "-dirty";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub time: i64,
+    pub commit: String,
+    pub machine: String,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub median_nanos: u64,
+}
+
+impl Record {
+    /// `None` for damaged lines, which are skipped
+    pub fn parse(line: &str) -> Option<Self> {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        match fields[..] {
+            [time, commit, machine, day, part, variant, median_nanos] => Some(Record {
+                time: time.parse().ok()?,
+                commit: commit.to_string(),
+                machine: machine.to_string(),
+                day: day.parse().ok()?,
+                part: part.parse().ok()?,
+                variant: variant.to_string(),
+                median_nanos: median_nanos.parse().ok()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// whether the run had uncommitted changes
+    pub fn is_dirty(&self) -> bool {
+        self.commit.ends_with(DIRTY)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.time,
+            self.commit,
+            self.machine,
+            self.day,
+            self.part,
+            self.variant,
+            self.median_nanos
+        )
+    }
+}
+
+/// The runs of one commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitTiming {
+    pub commit: String,
+    /// the time of the first run
+    pub time: i64,
+    pub runs: usize,
+    /// the median of the runs' medians
+    pub median_nanos: u64,
+}
+
+/// Groups the records by commit, in the order the commits were first benched, leaving out the
+/// runs with uncommitted changes
+pub fn by_commit<'a>(records: impl IntoIterator<Item = &'a Record>) -> Vec<CommitTiming> {
+    let mut commits: Vec<(String, i64, Vec<u64>)> = vec![];
+    for record in records.into_iter().filter(|record| !record.is_dirty()) {
+        match commits
+            .iter_mut()
+            .find(|(commit, _, _)| *commit == record.commit)
+        {
+            Some((_, _, medians)) => medians.push(record.median_nanos),
+            None => commits.push((
+                record.commit.clone(),
+                record.time,
+                vec![record.median_nanos],
+            )),
+        }
+    }
+
+    commits
+        .into_iter()
+        .map(|(commit, time, mut medians)| {
+            medians.sort_unstable();
+            CommitTiming {
+                commit,
+                time,
+                runs: medians.len(),
+                median_nanos: medians[medians.len() / 2],
+            }
+        })
+        .collect()
+}
+
+/// The change of each commit against the one benched before it in percent, `None` for the first
+pub fn changes(timings: &[CommitTiming]) -> Vec<Option<f64>> {
+    let previous = timings.iter().map(Some);
+    core::iter::once(None)
+        .chain(previous)
+        .zip(timings)
+        .map(|(previous, timing)| {
+            let previous = previous?.median_nanos as f64;
+            Some((timing.median_nanos as f64 / previous - 1.0) * 100.0)
+        })
+        .collect()
+}
+
+/// One block per value, from the lowest to the highest
+pub fn sparkline(values: &[u64]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().min().unwrap_or(0);
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| match max - min {
+            0 => BLOCKS[0],
+            range => BLOCKS[((value - min) as u128 * 7 / range as u128) as usize],
+        })
+        .collect()
+}
+
+/// `2021-12-01` for a unix time, in UTC
+pub fn format_date(time: i64) -> String {
+    // the inverse of `days_from_civil` in the leaderboard
+    let days = time.div_euclid(86400) + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+fn record(time: i64, commit: &str, median_nanos: u64) -> Record {
+    Record {
+        time,
+        commit: commit.to_string(),
+        machine: String::from("machine"),
+        day: 7,
+        part: 1,
+        variant: String::from("default"),
+        median_nanos,
+    }
+}
+
+#[test]
+fn records_round_trip() {
+    let record = record(1638334800, "abc", 1500);
+    assert_eq!(Record::parse(&record.to_string()), Some(record));
+    assert_eq!(Record::parse("1638334800\tabc"), None);
+}
+
+#[test]
+fn group_by_commit() {
+    let records = [
+        record(1, "a", 100),
+        record(2, "b", 300),
+        record(3, "a", 120),
+        record(4, "a", 110),
+        record(5, "b-dirty", 900),
+    ];
+    let timings = by_commit(&records);
+    assert_eq!(
+        timings
+            .iter()
+            .map(|timing| (
+                timing.commit.as_str(),
+                timing.time,
+                timing.runs,
+                timing.median_nanos
+            ))
+            .collect::<Vec<_>>(),
+        [("a", 1, 3, 110), ("b", 2, 1, 300)]
+    );
+    let changes = changes(&timings);
+    assert_eq!(changes[0], None);
+    assert_eq!(changes[1].map(f64::round), Some(173.0));
+}
+
+#[test]
+fn sparklines() {
+    assert_eq!(sparkline(&[1, 8, 4, 8]), "▁█▄█");
+    assert_eq!(sparkline(&[5, 5]), "▁▁");
+    assert_eq!(sparkline(&[]), "");
+}
+
+#[test]
+fn dates() {
+    assert_eq!(format_date(0), "1970-01-01");
+    assert_eq!(format_date(1638334800), "2021-12-01");
+    assert_eq!(format_date(951782400), "2000-02-29");
+}
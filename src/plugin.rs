@@ -0,0 +1,285 @@
+//! Solvers loaded at runtime from `cdylib` plugins, see the plugin section of
+//! `ffi/include/aoc2021.h`
+//!
+//! A plugin exports `aoc2021_plugin`, which returns a static [`PluginInfo`] with the
+//! [`ABI_VERSION`] it was built against, its name and its solvers. A solver gets the raw input and
+//! reports like `aoc2021_solve` of the C ABI in `ffi/`, a status code and a NUL-terminated answer
+//! or error message that is released with the plugin's `free`. `examples/day1_plugin.rs` is a
+//! complete plugin.
+//!
+//! Plugins are loaded with `dlopen`, so only on Unix, and never unloaded, their solvers live as
+//! long as the process.
+
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+use std::path::{Path, PathBuf};
+
+/// bumped whenever [`PluginInfo`] or [`PluginSolver`] change
+pub const ABI_VERSION: u32 = 1;
+
+/// the symbol every plugin exports
+pub const ENTRY_POINT: &str = "aoc2021_plugin";
+
+// the status codes of plugin solvers and of the C ABI's `aoc2021_solve`
+pub const OK: i32 = 0;
+pub const NULL_ARGUMENT: i32 = 1;
+pub const INVALID_UTF8: i32 = 2;
+pub const UNKNOWN_SOLVER: i32 = 3;
+pub const PANICKED: i32 = 4;
+
+pub type SolveFn =
+    unsafe extern "C" fn(input: *const u8, input_len: usize, answer: *mut *mut c_char) -> i32;
+pub type FreeFn = unsafe extern "C" fn(answer: *mut c_char);
+/// the type of [`ENTRY_POINT`]
+pub type EntryPoint = unsafe extern "C" fn() -> *const PluginInfo;
+
+/// One part of a day implemented by a plugin
+#[repr(C)]
+pub struct PluginSolver {
+    pub day: u8,
+    pub part: u8,
+    /// NUL-terminated, unique among the plugin's solvers of the same day and part
+    pub variant: *const c_char,
+    pub solve: SolveFn,
+}
+
+/// What `aoc2021_plugin` returns
+#[repr(C)]
+pub struct PluginInfo {
+    pub abi_version: u32,
+    /// NUL-terminated, prefixes the variant names of the solvers
+    pub name: *const c_char,
+    pub solvers: *const PluginSolver,
+    pub solver_count: usize,
+    pub free: FreeFn,
+}
+
+// plugins written in Rust keep these in statics, the pointers are to static data
+unsafe impl Sync for PluginSolver {}
+unsafe impl Sync for PluginInfo {}
+
+/// A loaded plugin
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+    pub solvers: Vec<Solver>,
+}
+
+/// A solver of a loaded plugin
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    /// `plugin/variant`, to tell it apart from the built-in variants
+    pub variant: String,
+    solve: SolveFn,
+    free: FreeFn,
+}
+
+impl Solver {
+    /// The answer, or the plugin's error message
+    pub fn solve(&self, input: &str) -> Result<String, String> {
+        let mut answer = std::ptr::null_mut();
+        let status = unsafe { (self.solve)(input.as_ptr(), input.len(), &mut answer) };
+        if answer.is_null() {
+            return Err(format!(
+                "{} returned status {} without an answer",
+                self.variant, status
+            ));
+        }
+        let text = unsafe { CStr::from_ptr(answer) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { (self.free)(answer) };
+        match status {
+            OK => Ok(text),
+            _ => Err(text),
+        }
+    }
+}
+
+#[cfg(unix)]
+const RTLD_NOW: c_int = 2;
+
+#[cfg(unix)]
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlerror() -> *const c_char;
+}
+
+#[cfg(unix)]
+fn dl_error() -> String {
+    let error = unsafe { dlerror() };
+    if error.is_null() {
+        String::from("unknown error")
+    } else {
+        unsafe { CStr::from_ptr(error) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// the text of a NUL-terminated string from a plugin
+#[cfg(unix)]
+fn text(pointer: *const c_char, what: &str) -> Result<String, String> {
+    if pointer.is_null() {
+        return Err(format!("{} is NULL", what));
+    }
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| format!("{} is not UTF-8", what))
+}
+
+/// Loads the plugin at `path` and checks what it declares
+#[cfg(unix)]
+pub fn load(path: &Path) -> Result<Plugin, String> {
+    let error = |message: String| format!("{}: {}", path.display(), message);
+    let filename = CString::new(path.as_os_str().as_encoded_bytes())
+        .map_err(|_| error(String::from("path contains NUL")))?;
+    let handle = unsafe { dlopen(filename.as_ptr(), RTLD_NOW) };
+    if handle.is_null() {
+        // names the file already
+        return Err(dl_error());
+    }
+
+    let symbol = CString::new(ENTRY_POINT).unwrap();
+    let entry_point = unsafe { dlsym(handle, symbol.as_ptr()) };
+    if entry_point.is_null() {
+        return Err(error(format!("not a plugin, {} is missing", ENTRY_POINT)));
+    }
+    let entry_point = unsafe { std::mem::transmute::<*mut c_void, EntryPoint>(entry_point) };
+    let info = unsafe { entry_point().as_ref() }
+        .ok_or_else(|| error(format!("{} returned NULL", ENTRY_POINT)))?;
+    if info.abi_version != ABI_VERSION {
+        return Err(error(format!(
+            "built for plugin ABI version {}, this runner supports version {}",
+            info.abi_version, ABI_VERSION
+        )));
+    }
+
+    let name = text(info.name, "the plugin name").map_err(error)?;
+    let solvers = match info.solver_count {
+        0 => &[][..],
+        _ if info.solvers.is_null() => return Err(error(String::from("solvers is NULL"))),
+        count => unsafe { std::slice::from_raw_parts(info.solvers, count) },
+    };
+    let solvers = solvers
+        .iter()
+        .map(|solver| {
+            let variant = text(solver.variant, "a variant name")?;
+            if !(1..=25).contains(&solver.day) || !(1..=2).contains(&solver.part) {
+                return Err(format!(
+                    "{} declares Day {} Part {}",
+                    variant, solver.day, solver.part
+                ));
+            }
+            Ok(Solver {
+                day: solver.day,
+                part: solver.part,
+                variant: format!("{}/{}", name, variant),
+                solve: solver.solve,
+                free: info.free,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(error)?;
+    if let Some(solver) = repeated_variant(&solvers) {
+        return Err(error(format!(
+            "declares {} twice for Day {} Part {}",
+            solver.variant, solver.day, solver.part
+        )));
+    }
+
+    Ok(Plugin {
+        name,
+        path: path.to_path_buf(),
+        solvers,
+    })
+}
+
+/// the first solver whose variant an earlier solver of the same part already has
+#[cfg(unix)]
+fn repeated_variant(solvers: &[Solver]) -> Option<&Solver> {
+    solvers.iter().enumerate().find_map(|(i, solver)| {
+        solvers[..i]
+            .iter()
+            .any(|earlier| {
+                (earlier.day, earlier.part, &earlier.variant)
+                    == (solver.day, solver.part, &solver.variant)
+            })
+            .then_some(solver)
+    })
+}
+
+/// Loads every shared library in `dir`, sorted by file name, a missing directory has none.
+/// A plugin is rejected if an earlier one has its name.
+#[cfg(unix)]
+pub fn load_dir(dir: &Path) -> Vec<Result<Plugin, String>> {
+    let mut paths = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| ["so", "dylib"].contains(&&*extension.to_string_lossy()))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    let mut plugins: Vec<Result<Plugin, String>> = vec![];
+    for path in &paths {
+        let plugin = load(path).and_then(|plugin| {
+            match plugins
+                .iter()
+                .flatten()
+                .find(|earlier| earlier.name == plugin.name)
+            {
+                Some(earlier) => Err(format!(
+                    "{}: the name {} is taken by {}",
+                    path.display(),
+                    plugin.name,
+                    earlier.path.display()
+                )),
+                None => Ok(plugin),
+            }
+        });
+        plugins.push(plugin);
+    }
+    plugins
+}
+
+/// Plugins need `dlopen`, elsewhere there are none
+#[cfg(not(unix))]
+pub fn load_dir(_dir: &Path) -> Vec<Result<Plugin, String>> {
+    vec![]
+}
+
+#[cfg(test)]
+unsafe extern "C" fn no_answer(
+    _input: *const u8,
+    _input_len: usize,
+    _answer: *mut *mut c_char,
+) -> i32 {
+    PANICKED
+}
+
+#[cfg(test)]
+unsafe extern "C" fn no_free(_answer: *mut c_char) {}
+
+#[cfg(unix)]
+#[test]
+fn finds_repeated_variants() {
+    let solver = |part, variant: &str| Solver {
+        day: 1,
+        part,
+        variant: format!("test/{}", variant),
+        solve: no_answer,
+        free: no_free,
+    };
+    let solvers = [solver(1, "fast"), solver(2, "fast"), solver(1, "slow")];
+    assert!(repeated_variant(&solvers).is_none());
+
+    let solvers = [solver(1, "fast"), solver(2, "fast"), solver(1, "fast")];
+    let repeated = repeated_variant(&solvers).unwrap();
+    assert_eq!((repeated.part, repeated.variant.as_str()), (1, "test/fast"));
+}
@@ -0,0 +1,16 @@
+//! The parts of the `std` prelude and collections the solvers use that also exist without `std`
+//!
+//! [`Map`] and [`Set`] are `std`'s `HashMap` and `HashSet` with the `std` feature. Without it there
+//! are no hash maps, the ordered `BTreeMap` and `BTreeSet` from `alloc` take their place, with
+//! logarithmic instead of constant time lookups. Keys need `Ord` as well as `Hash` to work with
+//! both.
+
+pub use alloc::boxed::Box;
+pub use alloc::string::{String, ToString};
+pub use alloc::vec::Vec;
+pub use alloc::{format, vec};
+
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::{BTreeMap as Map, BTreeSet as Set};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap as Map, HashSet as Set};
@@ -0,0 +1,465 @@
+use crate::budget::{Budget, Exhausted};
+use crate::input::normalize;
+use crate::json::Value;
+use crate::prelude::*;
+use crate::repl::Session;
+use crate::rng::Rng;
+use core::any::Any;
+
+/// The parsed input of any day, see [`parse`]
+#[cfg(feature = "std")]
+pub type Parsed = dyn Any + Send + Sync;
+/// The parsed input of any day, see [`parse`]
+///
+/// Not shareable between threads without `std`, day 19 caches its alignment in a `OnceCell`.
+#[cfg(not(feature = "std"))]
+pub type Parsed = dyn Any;
+
+pub type Solve = fn(&Parsed, &Budget) -> Result<String, Exhausted>;
+
+/// The parser of a day, shared by all its parts and variants
+pub struct Day {
+    pub day: u8,
+    pub parse: fn(&str) -> Box<Parsed>,
+}
+
+/// A named implementation of one part of a day
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub variant: &'static str,
+    pub solve: Solve,
+}
+
+/// Generates a random input for the day, `size` roughly scales the amount of input data
+pub struct Generator {
+    pub day: u8,
+    pub generate: fn(&mut Rng, usize) -> String,
+}
+
+/// Reports a day's intermediate results for a part, to see where a wrong answer comes from
+pub struct Explainer {
+    pub day: u8,
+    pub explain: fn(&Parsed, u8) -> Value,
+}
+
+/// Starts an interactive session on a day's input, see [`crate::repl`]
+pub struct Explorer {
+    pub day: u8,
+    pub start: fn(&str) -> Box<dyn Session>,
+}
+
+/// Renders a picture of the day's puzzle for the full input, e.g. a grid, for `run writeup`
+pub struct Visualizer {
+    pub day: u8,
+    pub visualize: fn(&Parsed) -> String,
+}
+
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// the first number in `name`, e.g. 12 for `day12` and 1 for `part1_within`
+const fn number(name: &str) -> u8 {
+    let bytes = name.as_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() && !bytes[idx].is_ascii_digit() {
+        idx += 1;
+    }
+    let mut number = 0;
+    while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+        number = number * 10 + (bytes[idx] - b'0');
+        idx += 1;
+    }
+    number
+}
+
+/// Takes the day's parse function only to infer the type, which ties a solver in the macros
+/// below to the `parse` of its own day. Handing it another day's parsed input at runtime still
+/// panics here.
+fn downcast<T: Any>(parsed: &Parsed, _parse: fn(&str) -> T) -> &T {
+    parsed
+        .downcast_ref()
+        .expect("parsed input of a different day")
+}
+
+macro_rules! day {
+    ($day:ident) => {
+        Day {
+            day: number(stringify!($day)),
+            parse: |input| Box::new(crate::$day::parse(&normalize(input))),
+        }
+    };
+}
+
+macro_rules! solver {
+    ($day:ident, $part:ident) => {
+        solver!($day, $part, DEFAULT_VARIANT)
+    };
+    ($day:ident, $part:ident, $variant:expr) => {
+        Solver {
+            day: number(stringify!($day)),
+            part: number(stringify!($part)),
+            variant: $variant,
+            solve: |parsed, _budget| {
+                let input = downcast(parsed, crate::$day::parse);
+                Ok(crate::$day::$part(input).to_string())
+            },
+        }
+    };
+}
+
+/// for solvers that check a [`Budget`]
+macro_rules! solver_within {
+    ($day:ident, $part:ident) => {
+        Solver {
+            day: number(stringify!($day)),
+            part: number(stringify!($part)),
+            variant: DEFAULT_VARIANT,
+            solve: |parsed, budget| {
+                let input = downcast(parsed, crate::$day::parse);
+                crate::$day::$part(input, budget).map(|result| result.to_string())
+            },
+        }
+    };
+}
+
+macro_rules! generator {
+    ($day:ident) => {
+        Generator {
+            day: number(stringify!($day)),
+            generate: crate::$day::generate,
+        }
+    };
+}
+
+macro_rules! explainer {
+    ($day:ident) => {
+        Explainer {
+            day: number(stringify!($day)),
+            explain: |parsed, part| {
+                crate::$day::explain(downcast(parsed, crate::$day::parse), part)
+            },
+        }
+    };
+}
+
+macro_rules! explorer {
+    ($day:ident) => {
+        Explorer {
+            day: number(stringify!($day)),
+            start: |input| crate::$day::repl(crate::$day::parse(&normalize(input))),
+        }
+    };
+}
+
+macro_rules! visualizer {
+    ($day:ident) => {
+        Visualizer {
+            day: number(stringify!($day)),
+            visualize: |parsed| crate::$day::visualize(downcast(parsed, crate::$day::parse)),
+        }
+    };
+}
+
+pub static DAYS: &[Day] = &[
+    day!(day1),
+    day!(day2),
+    day!(day3),
+    day!(day4),
+    day!(day5),
+    day!(day6),
+    day!(day7),
+    day!(day8),
+    day!(day9),
+    day!(day10),
+    day!(day11),
+    day!(day12),
+    day!(day13),
+    day!(day14),
+    day!(day15),
+    day!(day16),
+    day!(day17),
+    day!(day18),
+    day!(day19),
+    day!(day20),
+    day!(day21),
+    day!(day22),
+    day!(day23),
+    day!(day24),
+    day!(day25),
+    // gen_day_x inserts new days above this line
+];
+
+pub static SOLVERS: &[Solver] = &[
+    solver!(day1, part1),
+    solver!(day1, part2),
+    solver!(day1, part1_naive, "naive"),
+    solver!(day1, part2_naive, "naive"),
+    solver!(day2, part1),
+    solver!(day2, part2),
+    solver!(day3, part1),
+    solver!(day3, part2),
+    solver!(day4, part1),
+    solver!(day4, part2),
+    solver!(day5, part1),
+    solver!(day5, part2),
+    solver!(day6, part1),
+    solver!(day6, part2),
+    solver!(day6, part1_naive, "naive"),
+    solver!(day7, part1),
+    solver!(day7, part2),
+    solver!(day7, part2_mean, "mean"),
+    solver!(day8, part1),
+    solver!(day8, part2),
+    solver!(day9, part1),
+    solver!(day9, part2),
+    solver!(day10, part1),
+    solver!(day10, part2),
+    solver!(day11, part1),
+    solver!(day11, part2),
+    solver_within!(day12, part1_within),
+    solver_within!(day12, part2_within),
+    solver!(day13, part1),
+    solver!(day13, part2),
+    solver!(day14, part1),
+    solver!(day14, part2),
+    solver!(day15, part1),
+    solver!(day15, part2),
+    solver!(day16, part1),
+    solver!(day16, part2),
+    solver_within!(day17, part1_within),
+    solver_within!(day17, part2_within),
+    solver!(day18, part1),
+    solver!(day18, part2),
+    solver!(day19, part1),
+    solver!(day19, part2),
+    solver!(day20, part1),
+    solver!(day20, part2),
+    solver!(day21, part1),
+    solver!(day21, part2),
+    solver!(day22, part1),
+    solver!(day22, part2),
+    solver_within!(day23, part1_within),
+    solver_within!(day23, part2_within),
+    solver!(day24, part1),
+    solver!(day24, part1_expanded, "expanded"),
+    solver!(day24, part1_stepwise, "stepwise"),
+    solver!(day24, part2),
+    solver!(day25, part1),
+    solver!(day25, part2),
+    // gen_day_x inserts new solvers above this line
+];
+
+pub static GENERATORS: &[Generator] = &[
+    generator!(day1),
+    generator!(day4),
+    generator!(day6),
+    generator!(day7),
+    generator!(day15),
+    generator!(day18),
+];
+
+pub static EXPLAINERS: &[Explainer] = &[
+    explainer!(day4),
+    explainer!(day9),
+    explainer!(day17),
+    explainer!(day19),
+    explainer!(day22),
+];
+
+pub static EXPLORERS: &[Explorer] = &[
+    explorer!(day11),
+    explorer!(day13),
+    explorer!(day16),
+    explorer!(day18),
+    explorer!(day25),
+];
+
+pub static VISUALIZERS: &[Visualizer] = &[visualizer!(day11), visualizer!(day25)];
+
+/// all registered variants of the part, the default one first
+pub fn variants(day: u8, part: u8) -> impl Iterator<Item = &'static Solver> {
+    SOLVERS
+        .iter()
+        .filter(move |solver| solver.day == day && solver.part == part)
+}
+
+pub fn parse(day: u8, input: &str) -> Option<Box<Parsed>> {
+    DAYS.iter()
+        .find(|entry| entry.day == day)
+        .map(|entry| (entry.parse)(input))
+}
+
+pub fn solver(day: u8, part: u8, variant: &str) -> Option<&'static Solver> {
+    variants(day, part).find(|solver| solver.variant == variant)
+}
+
+// the hash of every `dayN.rs` and the shared modules, generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/source_hashes.rs"));
+
+/// Hash of the day's source file and of the library modules outside `bin/`, a change to either
+/// means its answers may have changed
+pub fn source_hash(day: u8) -> Option<u64> {
+    SOURCE_HASHES
+        .iter()
+        .find(|(source_day, _)| *source_day == day)
+        .map(|&(_, hash)| hash)
+}
+
+pub fn explainer(day: u8) -> Option<&'static Explainer> {
+    EXPLAINERS.iter().find(|explainer| explainer.day == day)
+}
+
+pub fn explorer(day: u8) -> Option<&'static Explorer> {
+    EXPLORERS.iter().find(|explorer| explorer.day == day)
+}
+
+pub fn visualizer(day: u8) -> Option<&'static Visualizer> {
+    VISUALIZERS.iter().find(|visualizer| visualizer.day == day)
+}
+
+pub fn generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+#[test]
+fn names_to_numbers() {
+    assert_eq!(number("day12"), 12);
+    assert_eq!(number("part1_within"), 1);
+    assert_eq!(number("part2_naive"), 2);
+}
+
+#[test]
+fn every_solver_has_a_day() {
+    for solver in SOLVERS {
+        assert!(DAYS.iter().any(|day| day.day == solver.day));
+    }
+}
+
+#[test]
+fn default_variant_first() {
+    for solver in SOLVERS {
+        let first = variants(solver.day, solver.part).next().unwrap();
+        assert_eq!(first.variant, DEFAULT_VARIANT);
+    }
+}
+
+#[test]
+#[cfg_attr(sealed_without_key, ignore = "sealed inputs and no key to open them")]
+fn variants_agree_on_full_input() {
+    let input_dir = crate::config::Config::of_crate().input_dir();
+    for variant in SOLVERS.iter().filter(|s| s.variant != DEFAULT_VARIANT) {
+        let input =
+            std::fs::read_to_string(input_dir.join(format!("day{}.txt", variant.day))).unwrap();
+        let input = crate::seal::open_in_crate(&input);
+        let parsed = parse(variant.day, &input).unwrap();
+        let default = solver(variant.day, variant.part, DEFAULT_VARIANT).unwrap();
+        assert_eq!(
+            (variant.solve)(&*parsed, &Budget::unlimited()),
+            (default.solve)(&*parsed, &Budget::unlimited()),
+            "Day {} Part {} Variant {}",
+            variant.day,
+            variant.part,
+            variant.variant
+        );
+    }
+}
+
+#[test]
+fn every_day_has_a_source_hash() {
+    for day in DAYS {
+        assert!(source_hash(day.day).is_some());
+    }
+}
+
+/// the day's first example, day 22 only has numbered ones
+#[cfg(test)]
+fn example_input(day: u8) -> String {
+    let input_dir = crate::config::Config::of_crate().input_dir();
+    ["example", "example1"]
+        .iter()
+        .find_map(|name| {
+            std::fs::read_to_string(input_dir.join(format!("day{}.{}.txt", day, name))).ok()
+        })
+        .unwrap_or_else(|| panic!("day {} has no example", day))
+}
+
+#[test]
+fn explainers_report_on_examples() {
+    for explainer in EXPLAINERS {
+        let input = example_input(explainer.day);
+        let parsed = parse(explainer.day, &input).unwrap();
+        for part in 1..=2 {
+            let report = (explainer.explain)(&*parsed, part);
+            assert!(report.as_object().is_some());
+            let number = |key| report.get(key).and_then(Value::as_i64);
+            match (explainer.day, part) {
+                (4, 1) => {
+                    assert_eq!(number("board"), Some(2));
+                    assert_eq!(number("round"), Some(12));
+                    assert_eq!(number("score"), Some(4512));
+                }
+                (4, 2) => {
+                    assert_eq!(number("board"), Some(1));
+                    assert_eq!(number("score"), Some(1924));
+                }
+                (9, 1) => assert_eq!(number("count"), Some(4)),
+                (9, 2) => assert_eq!(report.get("largest"), Some(&Value::from(vec![14, 9, 9]))),
+                (17, _) => {
+                    let highest = report
+                        .get("highest")
+                        .and_then(|highest| highest.get("height"));
+                    assert_eq!(highest.and_then(Value::as_i64), Some(45));
+                    assert_eq!(number("count"), Some(112));
+                }
+                (19, 1) => assert_eq!(number("beacons"), Some(79)),
+                (19, 2) => {
+                    let farthest = report
+                        .get("farthest")
+                        .and_then(|farthest| farthest.get("distance"));
+                    assert_eq!(farthest.and_then(Value::as_i64), Some(3621));
+                }
+                // the example has no part 2 answer, only cubes outside the initialization region
+                // add to part 1's
+                (22, 1) => assert_eq!(number("cubes_on"), Some(590784)),
+                (22, 2) => assert!(number("cubes_on").is_some_and(|cubes| cubes > 590784)),
+                (day, part) => panic!("no known values for day {} part {}", day, part),
+            }
+        }
+    }
+}
+
+#[test]
+fn explorers_start_on_examples() {
+    for explorer in EXPLORERS {
+        let input = example_input(explorer.day);
+        let mut session = (explorer.start)(&input);
+        let help = session.execute("help").unwrap();
+        let first = help.split_whitespace().next().unwrap();
+        assert!(
+            session.execute(first).is_ok(),
+            "day {} {}",
+            explorer.day,
+            first
+        );
+    }
+}
+
+#[test]
+fn visualizers_render_examples() {
+    for visualizer in VISUALIZERS {
+        let input = example_input(visualizer.day);
+        let parsed = parse(visualizer.day, &input).unwrap();
+        assert!(!(visualizer.visualize)(&*parsed).is_empty());
+    }
+}
+
+#[test]
+fn generated_inputs_solve() {
+    for generator in GENERATORS {
+        let input = (generator.generate)(&mut Rng::new(1), 50);
+        let parsed = parse(generator.day, &input).unwrap();
+        for solver in variants(generator.day, 1).chain(variants(generator.day, 2)) {
+            assert!((solver.solve)(&*parsed, &Budget::unlimited()).is_ok());
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! A small command framework for exploring a day's parsed input interactively, see `run repl`
+//!
+//! A day opts in with a `repl` function that wraps its state and a table of [`Command`]s in a
+//! [`Repl`], and an `explorer!` entry in the registry. `help` is built in for every day.
+
+use crate::prelude::*;
+use core::str::FromStr;
+
+/// A command of a day, `run` gets the words after the name and returns what to print
+pub struct Command<S> {
+    pub name: &'static str,
+    /// the arguments, e.g. `[count]`, shown by `help`
+    pub args: &'static str,
+    pub help: &'static str,
+    pub run: fn(&mut S, &[&str]) -> Result<String, String>,
+}
+
+/// An interactive session over a day's state
+pub trait Session {
+    /// runs one command line and returns what to print
+    fn execute(&mut self, line: &str) -> Result<String, String>;
+}
+
+/// A [`Session`] dispatching to a table of commands
+pub struct Repl<S: 'static> {
+    state: S,
+    commands: &'static [Command<S>],
+}
+
+impl<S> Repl<S> {
+    pub fn new(state: S, commands: &'static [Command<S>]) -> Self {
+        Repl { state, commands }
+    }
+
+    fn help(&self) -> String {
+        let width = self
+            .commands
+            .iter()
+            .map(|command| command.name.len() + command.args.len() + 1)
+            .max()
+            .unwrap_or(0);
+        let mut help = String::new();
+        for command in self.commands {
+            let usage = format!("{} {}", command.name, command.args);
+            help += &format!("{:<width$}  {}\n", usage, command.help, width = width);
+        }
+        help + &format!("{:<width$}  {}", "help", "show this list", width = width)
+    }
+}
+
+impl<S> Session for Repl<S> {
+    fn execute(&mut self, line: &str) -> Result<String, String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        match words.split_first() {
+            None => Ok(String::new()),
+            Some((&"help", _)) => Ok(self.help()),
+            Some((name, args)) => {
+                let command = self
+                    .commands
+                    .iter()
+                    .find(|command| command.name == *name)
+                    .ok_or_else(|| format!("unknown command {}, try help", name))?;
+                (command.run)(&mut self.state, args)
+            }
+        }
+    }
+}
+
+/// the argument at `idx`, or `default` if there is none
+pub fn arg<T: FromStr>(args: &[&str], idx: usize, default: T) -> Result<T, String> {
+    match args.get(idx) {
+        Some(arg) => arg.parse().map_err(|_| format!("invalid argument {}", arg)),
+        None => Ok(default),
+    }
+}
+
+#[test]
+fn dispatch_commands() {
+    const COMMANDS: &[Command<u32>] = &[Command {
+        name: "add",
+        args: "[amount]",
+        help: "add to the counter",
+        run: |counter, args| {
+            *counter += arg(args, 0, 1)?;
+            Ok(counter.to_string())
+        },
+    }];
+    let mut repl = Repl::new(0, COMMANDS);
+    assert_eq!(repl.execute("add"), Ok(String::from("1")));
+    assert_eq!(repl.execute("  add 5 "), Ok(String::from("6")));
+    assert_eq!(repl.execute(""), Ok(String::new()));
+    assert!(repl.execute("add x").is_err());
+    assert!(repl.execute("sub").is_err());
+    assert!(repl
+        .execute("help")
+        .unwrap()
+        .starts_with("add [amount]  add to the counter"));
+}
@@ -0,0 +1,60 @@
+use core::ops::Range;
+
+/// Small seeded pseudo random number generator (SplitMix64) for generating puzzle inputs
+///
+/// Same seed, same sequence, so generated inputs are reproducible.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniformly distributed in the range, up to a negligible modulo bias
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "empty range");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.range(0..idx as u64 + 1) as usize);
+        }
+    }
+}
+
+#[test]
+fn same_seed_same_sequence() {
+    let mut a = Rng::new(42);
+    let mut b = Rng::new(42);
+    for _ in 0..10 {
+        assert_eq!(a.next_u64(), b.next_u64());
+    }
+}
+
+#[test]
+fn range_stays_in_bounds() {
+    let mut rng = Rng::new(7);
+    assert!((0..1000).all(|_| (3..9).contains(&rng.range(3..9))));
+}
+
+#[test]
+fn shuffle_permutes() {
+    let mut items = (0..100).collect::<Vec<_>>();
+    Rng::new(3).shuffle(&mut items);
+    assert_ne!(items, (0..100).collect::<Vec<_>>());
+    items.sort();
+    assert_eq!(items, (0..100).collect::<Vec<_>>());
+}
@@ -0,0 +1,251 @@
+//! Puzzle inputs encrypted at rest, so personal inputs can be committed without sharing them
+//!
+//! Sealed inputs are ChaCha20 encrypted (RFC 8439) with a random nonce and stored as hex:
+//!
+//! ```text
+//! aoc2021 sealed input v1
+//! <nonce>
+//! <ciphertext, 64 hex digits per line>
+//! ```
+//!
+//! The plaintext is prefixed with a known marker to detect a wrong key. There is no
+//! authentication beyond that, this keeps inputs out of plain sight and nothing more.
+//! The key is 64 hex digits from the `AOC2021_INPUT_KEY` environment variable or the
+//! git-ignored key file, `key_path` in `aoc.toml` or else `.input-key` next to it, see
+//! [`local_key`].
+
+#[cfg(any(feature = "std", test))]
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+const HEADER: &str = "aoc2021 sealed input v1";
+const MARKER: &[u8] = b"aoc2021 input\n";
+const HEX_DIGITS_PER_LINE: usize = 64;
+
+pub const KEY_VARIABLE: &str = "AOC2021_INPUT_KEY";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SealError {
+    MissingKey,
+    InvalidKey,
+    Malformed,
+    WrongKey,
+}
+
+impl core::fmt::Display for SealError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            SealError::MissingKey => write!(
+                f,
+                "sealed input but no key, set {} or create the key file, `key_path` in aoc.toml",
+                KEY_VARIABLE
+            ),
+            SealError::InvalidKey => write!(f, "the input key must be 64 hex digits"),
+            SealError::Malformed => write!(f, "malformed sealed input"),
+            SealError::WrongKey => write!(f, "sealed input does not match the key"),
+        }
+    }
+}
+
+impl core::error::Error for SealError {}
+
+/// 256 bit ChaCha20 key, deliberately without `Debug` so it does not end up in logs
+#[derive(Clone, PartialEq, Eq)]
+pub struct Key([u8; 32]);
+
+impl Key {
+    pub fn new(bytes: [u8; 32]) -> Self {
+        Key(bytes)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, SealError> {
+        let bytes = from_hex(hex.trim()).ok_or(SealError::InvalidKey)?;
+        bytes.try_into().map(Key).map_err(|_| SealError::InvalidKey)
+    }
+
+    pub fn to_hex(&self) -> String {
+        to_hex(&self.0)
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .map(|digit| char::from_digit(digit.into(), 16).unwrap())
+        .collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let digits = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<_>>>()?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| pair[0] << 4 | pair[1])
+            .collect(),
+    )
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn block(key: &Key, nonce: &[u8; 12], counter: u32) -> [u8; 64] {
+    let word = |bytes: &[u8]| u32::from_le_bytes(bytes.try_into().unwrap());
+
+    let mut initial = [0; 16];
+    initial[..4].copy_from_slice(&[0x6170_7865, 0x3320_646e, 0x7962_2d32, 0x6b20_6574]);
+    for (idx, chunk) in key.0.chunks(4).enumerate() {
+        initial[4 + idx] = word(chunk);
+    }
+    initial[12] = counter;
+    for (idx, chunk) in nonce.chunks(4).enumerate() {
+        initial[13 + idx] = word(chunk);
+    }
+
+    let mut state = initial;
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut output = [0; 64];
+    for (idx, (word, initial)) in state.iter().zip(initial).enumerate() {
+        output[idx * 4..idx * 4 + 4].copy_from_slice(&word.wrapping_add(initial).to_le_bytes());
+    }
+    output
+}
+
+/// encrypts or decrypts `data` in place, the first block uses `counter`
+pub fn chacha20(key: &Key, nonce: &[u8; 12], counter: u32, data: &mut [u8]) {
+    for (idx, chunk) in data.chunks_mut(64).enumerate() {
+        let keystream = block(key, nonce, counter.wrapping_add(idx as u32));
+        for (byte, key) in chunk.iter_mut().zip(keystream) {
+            *byte ^= key;
+        }
+    }
+}
+
+pub fn is_sealed(text: &str) -> bool {
+    text.starts_with(HEADER)
+}
+
+/// The sealed form of `input`, `nonce` must never be reused with the same key
+pub fn seal(key: &Key, nonce: [u8; 12], input: &str) -> String {
+    let mut data = [MARKER, input.as_bytes()].concat();
+    chacha20(key, &nonce, 1, &mut data);
+
+    let mut sealed = String::from(HEADER);
+    sealed += "\n";
+    sealed += &to_hex(&nonce);
+    sealed += "\n";
+    for line in data.chunks(HEX_DIGITS_PER_LINE / 2) {
+        sealed += &to_hex(line);
+        sealed += "\n";
+    }
+    sealed
+}
+
+pub fn unseal(key: &Key, sealed: &str) -> Result<String, SealError> {
+    let mut lines = sealed.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(SealError::Malformed);
+    }
+    let nonce = lines
+        .next()
+        .and_then(from_hex)
+        .and_then(|nonce| nonce.try_into().ok())
+        .ok_or(SealError::Malformed)?;
+    let mut data =
+        from_hex(&lines.map(str::trim).collect::<String>()).ok_or(SealError::Malformed)?;
+
+    chacha20(key, &nonce, 1, &mut data);
+    let input = data.strip_prefix(MARKER).ok_or(SealError::WrongKey)?;
+    String::from_utf8(input.to_vec()).map_err(|_| SealError::WrongKey)
+}
+
+/// The key from `AOC2021_INPUT_KEY`, or else from the file at `key_path`
+#[cfg(any(feature = "std", test))]
+pub fn local_key(key_path: &std::path::Path) -> Result<Key, SealError> {
+    if let Ok(hex) = std::env::var(KEY_VARIABLE) {
+        return Key::from_hex(&hex);
+    }
+    match std::fs::read_to_string(key_path) {
+        Ok(hex) => Key::from_hex(&hex),
+        Err(_) => Err(SealError::MissingKey),
+    }
+}
+
+/// The plaintext of an input file's content, which may or may not be sealed
+#[cfg(any(feature = "std", test))]
+pub fn open<'a>(text: &'a str, key_path: &std::path::Path) -> Result<Cow<'a, str>, SealError> {
+    if is_sealed(text) {
+        unseal(&local_key(key_path)?, text).map(Cow::Owned)
+    } else {
+        Ok(Cow::Borrowed(text))
+    }
+}
+
+/// [`open`] with the key of this checkout, for the tests of the full inputs
+#[cfg(test)]
+pub fn open_in_crate(text: &str) -> Cow<'_, str> {
+    let key_path = crate::config::Config::of_crate().key_path();
+    open(text, &key_path).unwrap_or_else(|err| panic!("{}", err))
+}
+
+#[test]
+fn rfc8439_encryption() {
+    let key = Key::new(core::array::from_fn(|idx| idx as u8));
+    let nonce = [0, 0, 0, 0, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+    let mut data = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.".to_vec();
+    chacha20(&key, &nonce, 1, &mut data);
+    assert_eq!(
+        to_hex(&data[..32]),
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b"
+    );
+    assert_eq!(to_hex(&data[data.len() - 2..]), "874d");
+}
+
+#[test]
+fn seal_round_trip() {
+    let key = Key::new([7; 32]);
+    let input = "1,2,3\n4,5,6\n";
+    let sealed = seal(&key, [1; 12], input);
+    assert!(is_sealed(&sealed));
+    assert!(!sealed.contains("1,2,3"));
+    assert_eq!(unseal(&key, &sealed).as_deref(), Ok(input));
+    assert_eq!(
+        unseal(&Key::new([8; 32]), &sealed),
+        Err(SealError::WrongKey)
+    );
+}
+
+#[test]
+fn key_hex() {
+    let key = Key::new([0xab; 32]);
+    assert_eq!(Key::from_hex(&key.to_hex()).unwrap().0, key.0);
+    assert_eq!(
+        Key::from_hex("abc").map(|key| key.0),
+        Err(SealError::InvalidKey)
+    );
+}
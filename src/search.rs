@@ -0,0 +1,360 @@
+//! Graph searches over implicit graphs, given a start state and a function for its neighbours
+//!
+//! States are keys of the prelude's [`Map`], a hash map or an ordered one depending on the `std`
+//! feature, so they need `Ord` as well as `Hash`. A [`Search`] is configured with a [`Budget`]
+//! and whether to keep the path to the goal, the free functions run unlimited and without paths.
+
+use crate::budget::{Budget, Exhausted};
+use crate::prelude::*;
+use alloc::collections::{BinaryHeap, VecDeque};
+use core::cmp::Reverse;
+use core::hash::Hash;
+use core::ops::Add;
+
+/// What a search state needs
+pub trait State: Clone + Eq + Hash + Ord {}
+
+impl<T: Clone + Eq + Hash + Ord> State for T {}
+
+/// What a cost needs, `Default` is zero
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// How much work a search did
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// states whose neighbours were asked for
+    pub expanded: usize,
+    /// distinct states seen
+    pub discovered: usize,
+}
+
+/// The goal state a search reached
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub state: S,
+    /// the number of steps for [`Search::bfs`]
+    pub cost: C,
+    /// from the start to `state`, both included, when asked for with [`Search::with_paths`]
+    pub path: Option<Vec<S>>,
+    pub stats: Stats,
+}
+
+/// The states seen so far, with the one each was reached from
+struct Nodes<S> {
+    states: Vec<S>,
+    index: Map<S, usize>,
+    parents: Vec<usize>,
+}
+
+impl<S: State> Nodes<S> {
+    fn new(start: S) -> Self {
+        Nodes {
+            states: vec![start.clone()],
+            index: Map::from([(start, 0)]),
+            parents: vec![0],
+        }
+    }
+
+    /// the index of `state` and whether it is new
+    fn insert(&mut self, state: S, parent: usize) -> (usize, bool) {
+        if let Some(&idx) = self.index.get(&state) {
+            return (idx, false);
+        }
+        let idx = self.states.len();
+        self.index.insert(state.clone(), idx);
+        self.states.push(state);
+        self.parents.push(parent);
+        (idx, true)
+    }
+
+    fn path(&self, mut idx: usize) -> Vec<S> {
+        let mut path = vec![self.states[idx].clone()];
+        while idx != 0 {
+            idx = self.parents[idx];
+            path.push(self.states[idx].clone());
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// The configuration of a search, e.g. `Search::new().within(budget).dijkstra(..)`
+#[derive(Clone, Copy, Default)]
+pub struct Search<'a> {
+    budget: Option<&'a Budget>,
+    paths: bool,
+}
+
+impl<'a> Search<'a> {
+    pub fn new() -> Self {
+        Search::default()
+    }
+
+    /// Checks the budget before every expansion
+    pub fn within(self, budget: &'a Budget) -> Self {
+        Search {
+            budget: Some(budget),
+            ..self
+        }
+    }
+
+    /// Keeps the path to the goal in [`Found::path`]
+    pub fn with_paths(self) -> Self {
+        Search {
+            paths: true,
+            ..self
+        }
+    }
+
+    fn check(&self) -> Result<(), Exhausted> {
+        self.budget.map_or(Ok(()), Budget::check)
+    }
+
+    fn found<S: State, C>(
+        &self,
+        nodes: &Nodes<S>,
+        idx: usize,
+        cost: C,
+        stats: Stats,
+    ) -> Found<S, C> {
+        Found {
+            state: nodes.states[idx].clone(),
+            cost,
+            path: self.paths.then(|| nodes.path(idx)),
+            stats: Stats {
+                discovered: nodes.states.len(),
+                ..stats
+            },
+        }
+    }
+
+    /// Breadth-first search for the goal fewest steps away
+    pub fn bfs<S: State, I: IntoIterator<Item = S>>(
+        &self,
+        start: S,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut goal: impl FnMut(&S) -> bool,
+    ) -> Result<Option<Found<S, usize>>, Exhausted> {
+        let mut nodes = Nodes::new(start);
+        let mut steps = vec![0];
+        let mut todo = VecDeque::from([0]);
+        let mut stats = Stats::default();
+
+        while let Some(idx) = todo.pop_front() {
+            if goal(&nodes.states[idx]) {
+                return Ok(Some(self.found(&nodes, idx, steps[idx], stats)));
+            }
+            self.check()?;
+            stats.expanded += 1;
+            for next in neighbours(&nodes.states[idx]) {
+                let (next_idx, new) = nodes.insert(next, idx);
+                if new {
+                    steps.push(steps[idx] + 1);
+                    todo.push_back(next_idx);
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Every state reachable from `start`, in breadth-first order starting with `start`
+    pub fn reachable<S: State, I: IntoIterator<Item = S>>(
+        &self,
+        start: S,
+        mut neighbours: impl FnMut(&S) -> I,
+    ) -> Result<Vec<S>, Exhausted> {
+        let mut nodes = Nodes::new(start);
+        let mut idx = 0;
+        while idx < nodes.states.len() {
+            self.check()?;
+            for next in neighbours(&nodes.states[idx]) {
+                nodes.insert(next, idx);
+            }
+            idx += 1;
+        }
+        Ok(nodes.states)
+    }
+
+    /// Dijkstra's algorithm for the cheapest goal, step costs must not be negative
+    pub fn dijkstra<S: State, C: Cost, I: IntoIterator<Item = (S, C)>>(
+        &self,
+        start: S,
+        neighbours: impl FnMut(&S) -> I,
+        goal: impl FnMut(&S) -> bool,
+    ) -> Result<Option<Found<S, C>>, Exhausted> {
+        self.astar(start, neighbours, |_| C::default(), goal)
+    }
+
+    /// A* search for the cheapest goal, the heuristic must never overestimate the remaining cost
+    /// and must not drop by more than the cost of a step
+    pub fn astar<S: State, C: Cost, I: IntoIterator<Item = (S, C)>>(
+        &self,
+        start: S,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut heuristic: impl FnMut(&S) -> C,
+        mut goal: impl FnMut(&S) -> bool,
+    ) -> Result<Option<Found<S, C>>, Exhausted> {
+        let mut heap = BinaryHeap::from([Reverse((heuristic(&start), 0))]);
+        let mut nodes = Nodes::new(start);
+        let mut costs = vec![C::default()];
+        let mut done = vec![false];
+        let mut stats = Stats::default();
+
+        while let Some(Reverse((_, idx))) = heap.pop() {
+            if done[idx] {
+                continue;
+            }
+            done[idx] = true;
+            if goal(&nodes.states[idx]) {
+                return Ok(Some(self.found(&nodes, idx, costs[idx], stats)));
+            }
+            self.check()?;
+            stats.expanded += 1;
+            for (next, step) in neighbours(&nodes.states[idx]) {
+                let cost = costs[idx] + step;
+                let (next_idx, new) = nodes.insert(next, idx);
+                if new {
+                    costs.push(cost);
+                    done.push(false);
+                } else if done[next_idx] || cost >= costs[next_idx] {
+                    continue;
+                } else {
+                    costs[next_idx] = cost;
+                    nodes.parents[next_idx] = idx;
+                }
+                heap.push(Reverse((
+                    cost + heuristic(&nodes.states[next_idx]),
+                    next_idx,
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The number of paths from `start` to a goal, goals are not expanded
+    ///
+    /// States are not deduplicated, every path is walked on its own, so the neighbours have to
+    /// make sure that there are finitely many.
+    pub fn count_paths<S, I: IntoIterator<Item = S>>(
+        &self,
+        start: S,
+        mut neighbours: impl FnMut(&S) -> I,
+        mut goal: impl FnMut(&S) -> bool,
+    ) -> Result<usize, Exhausted> {
+        let mut todo = vec![start];
+        let mut count = 0;
+        while let Some(state) = todo.pop() {
+            if goal(&state) {
+                count += 1;
+                continue;
+            }
+            self.check()?;
+            todo.extend(neighbours(&state));
+        }
+        Ok(count)
+    }
+}
+
+/// The searches of an unlimited [`Search`] cannot run out of budget
+fn unlimited<T>(result: Result<T, Exhausted>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(Exhausted) => unreachable!("a search without a budget ran out of it"),
+    }
+}
+
+/// [`Search::bfs`] without a budget
+pub fn bfs<S: State, I: IntoIterator<Item = S>>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>> {
+    unlimited(Search::new().bfs(start, neighbours, goal))
+}
+
+/// [`Search::reachable`] without a budget
+pub fn reachable<S: State, I: IntoIterator<Item = S>>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+) -> Vec<S> {
+    unlimited(Search::new().reachable(start, neighbours))
+}
+
+/// [`Search::dijkstra`] without a budget
+pub fn dijkstra<S: State, C: Cost, I: IntoIterator<Item = (S, C)>>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>> {
+    unlimited(Search::new().dijkstra(start, neighbours, goal))
+}
+
+/// [`Search::astar`] without a budget
+pub fn astar<S: State, C: Cost, I: IntoIterator<Item = (S, C)>>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>> {
+    unlimited(Search::new().astar(start, neighbours, heuristic, goal))
+}
+
+/// a line of ten nodes, each step right costs 1 and jumping two costs 3
+#[cfg(test)]
+fn line(&node: &u8) -> Vec<(u8, u32)> {
+    [(node + 1, 1), (node + 2, 3)]
+        .into_iter()
+        .filter(|&(next, _)| next < 10)
+        .collect()
+}
+
+/// the line without costs
+#[cfg(test)]
+fn unweighted(node: &u8) -> Vec<u8> {
+    line(node).into_iter().map(|(next, _)| next).collect()
+}
+
+#[test]
+fn bfs_counts_steps() {
+    let found = Search::new()
+        .with_paths()
+        .bfs(0, unweighted, |&node| node == 9)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.cost, 5);
+    assert_eq!(found.path, Some(vec![0, 1, 3, 5, 7, 9]));
+    assert_eq!(bfs(0u8, |_| [], |&node| node == 9), None);
+}
+
+#[test]
+fn dijkstra_and_astar_find_the_cheapest_path() {
+    let found = dijkstra(0, line, |&node| node == 9).unwrap();
+    assert_eq!((found.cost, found.path), (9, None));
+
+    let found = Search::new()
+        .with_paths()
+        .astar(0, line, |&node| 9 - node as u32, |&node| node == 9)
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.cost, 9);
+    assert_eq!(found.path, Some((0..10).collect()));
+    assert!(found.stats.expanded <= 9);
+}
+
+#[test]
+fn reachable_and_count_paths() {
+    assert_eq!(reachable(0, unweighted), (0..10).collect::<Vec<_>>());
+    // the Fibonacci numbers count the ways to climb stairs one or two steps at a time
+    let paths = Search::new().count_paths(0, unweighted, |&node| node == 9);
+    assert_eq!(paths, Ok(55));
+}
+
+#[test]
+fn budget_ends_the_search() {
+    let budget = Budget::unlimited();
+    budget.cancel_handle().cancel();
+    let search = Search::new().within(&budget);
+    assert_eq!(search.dijkstra(0, line, |&node| node == 9), Err(Exhausted));
+}
@@ -0,0 +1,160 @@
+//! The doc comments of a day's source, for the write-ups of `run writeup`
+//!
+//! A day's module docs are its leading `//!` lines. Item docs are the `///` lines in front of a
+//! function, type, constant or impl block, docs of fields and enum variants are left out.
+
+use crate::prelude::*;
+
+/// A documented item, e.g. `fn max_height` with its doc comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemDoc {
+    pub item: String,
+    pub doc: String,
+}
+
+const KEYWORDS: &[&str] = &[
+    "fn",
+    "struct",
+    "enum",
+    "const",
+    "static",
+    "type",
+    "trait",
+    "impl",
+    "mod",
+    "macro_rules!",
+];
+
+/// the text of a doc comment line, `None` for other lines
+fn doc_line<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let text = line.trim_start().strip_prefix(marker)?;
+    // `////` is a plain comment
+    if text.starts_with('/') {
+        return None;
+    }
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+/// `fn name` for an item line, `None` for fields, variants and statements
+fn item_name(line: &str) -> Option<String> {
+    let mut rest = line.trim_start();
+    if let Some(visibility) = rest.strip_prefix("pub") {
+        rest = match visibility.strip_prefix('(') {
+            Some(scope) => scope.split_once(')')?.1,
+            None => visibility,
+        }
+        .trim_start();
+    }
+    for qualifier in ["const ", "unsafe ", "async ", "extern \"C\" "] {
+        if let Some(after) = rest.strip_prefix(qualifier) {
+            // `const NAME` is an item of its own
+            if qualifier != "const " || after.starts_with("fn ") {
+                rest = after.trim_start();
+            }
+        }
+    }
+
+    let keyword = KEYWORDS.iter().find(|keyword| {
+        rest.strip_prefix(**keyword)
+            .is_some_and(|after| after.starts_with([' ', '<']))
+    })?;
+    if *keyword == "impl" {
+        // the impl header, e.g. `impl<T> Display for Grid<T>`
+        return Some(
+            rest.split('{')
+                .next()
+                .unwrap_or(rest)
+                .trim_end()
+                .to_string(),
+        );
+    }
+    let after = rest[keyword.len()..].trim_start();
+    let name = after
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .filter(|name| !name.is_empty())?;
+    Some(format!("{} {}", keyword.trim_end_matches('!'), name))
+}
+
+/// The leading `//!` lines, joined into Markdown paragraphs
+pub fn module_docs(source: &str) -> String {
+    let lines = source
+        .lines()
+        .map_while(|line| doc_line(line, "//!"))
+        .collect::<Vec<_>>();
+    lines.join("\n").trim().to_string()
+}
+
+/// The documented items in source order
+pub fn item_docs(source: &str) -> Vec<ItemDoc> {
+    let mut items = vec![];
+    let mut doc = vec![];
+    for line in source.lines() {
+        if let Some(text) = doc_line(line, "///") {
+            doc.push(text);
+        } else if line.trim_start().starts_with("#[") && !doc.is_empty() {
+            // attributes between the doc comment and the item
+        } else {
+            if !doc.is_empty() {
+                if let Some(item) = item_name(line) {
+                    items.push(ItemDoc {
+                        item,
+                        doc: doc.join("\n").trim().to_string(),
+                    });
+                }
+            }
+            doc.clear();
+        }
+    }
+    items
+}
+
+#[test]
+fn extract_docs() {
+    let source = "\
+//! How it works
+//!
+//! In two steps.
+
+use crate::prelude::*;
+
+/// The highest point
+#[inline]
+pub fn max_height(y: i32) -> i32 {
+    y * (y + 1) / 2
+}
+
+pub struct Probe {
+    /// left out
+    pub x: i32,
+}
+
+//// not a doc comment
+fn plain() {}
+
+/// Shows the grid
+impl<T: Display> Display for Grid<T> {}
+
+/// Limit
+pub(crate) const fn limit() -> u8 { 3 }
+";
+    assert_eq!(module_docs(source), "How it works\n\nIn two steps.");
+    let items = item_docs(source)
+        .into_iter()
+        .map(|item| (item.item, item.doc))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items,
+        [
+            (
+                String::from("fn max_height"),
+                String::from("The highest point")
+            ),
+            (
+                String::from("impl<T: Display> Display for Grid<T>"),
+                String::from("Shows the grid")
+            ),
+            (String::from("fn limit"), String::from("Limit")),
+        ]
+    );
+}
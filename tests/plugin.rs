@@ -0,0 +1,56 @@
+//! Loads the example plugin the way the runner does
+#![cfg(target_os = "linux")]
+
+use aoc2021::plugin;
+use std::path::PathBuf;
+
+/// cargo builds the examples into `examples` next to the `deps` directory of the test binary
+fn example_plugin() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let target_dir = exe.parent().unwrap().parent().unwrap();
+    target_dir.join("examples/libday1_plugin.so")
+}
+
+#[test]
+fn solves_through_plugin() {
+    let plugin = plugin::load(&example_plugin()).unwrap();
+    let input = include_str!("../input/day1.example.txt");
+
+    assert_eq!(plugin.name, "example");
+    let answers = plugin
+        .solvers
+        .iter()
+        .map(|solver| {
+            (
+                solver.day,
+                solver.part,
+                solver.variant.as_str(),
+                solver.solve(input),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        answers,
+        [
+            (1, 1, "example/window", Ok(String::from("7"))),
+            (1, 2, "example/window", Ok(String::from("5")))
+        ]
+    );
+    assert!(plugin.solvers[0].solve("no numbers").is_err());
+}
+
+#[test]
+fn rejects_a_second_plugin_with_the_same_name() {
+    let dir = std::env::temp_dir().join(format!("aoc2021-plugins-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["a.so", "b.so"] {
+        std::fs::copy(example_plugin(), dir.join(name)).unwrap();
+    }
+
+    let plugins = plugin::load_dir(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(plugins.len(), 2);
+    assert_eq!(plugins[0].as_ref().unwrap().name, "example");
+    let err = plugins[1].as_ref().err().unwrap();
+    assert!(err.contains("the name example is taken"), "{}", err);
+}